@@ -1,7 +1,9 @@
 use alloy::signers::local::PrivateKeySigner;
 use log::info;
 
-use hyperliquid_rust_sdk::{BaseUrl, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient};
+use hyperliquid_rust_sdk::{
+    BaseUrl, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient, Grouping, Side, Tif,
+};
 
 #[tokio::main]
 async fn main() {
@@ -33,17 +35,18 @@ async fn main() {
 
     let order = ClientOrderRequest {
         asset: "ETH".to_string(),
-        is_buy: true,
+        side: Side::Buy,
         reduce_only: false,
         limit_px: 1795.0,
         sz: 0.01,
         cloid: None,
-        order_type: ClientOrder::Limit(ClientLimit {
-            tif: "Gtc".to_string(),
-        }),
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
     };
 
-    let response = exchange_client.order(order, None).await.unwrap();
+    let response = exchange_client
+        .order(order, None, Grouping::Na)
+        .await
+        .unwrap();
 
     info!("Order placed: {response:?}");
 }
@@ -0,0 +1,39 @@
+use alloy::signers::{local::PrivateKeySigner, Signer};
+use log::info;
+
+use hyperliquid_rust_sdk::{
+    BaseUrl, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient, Grouping, Side, Tif,
+};
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: PrivateKeySigner =
+        "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .unwrap();
+
+    // Any signer can be boxed the same way, e.g. a Ledger or a remote KMS signer, so the key never enters this process
+    let wallet: Box<dyn Signer + Send + Sync> = Box::new(wallet);
+
+    let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
+        .await
+        .unwrap();
+
+    let order = ClientOrderRequest {
+        asset: "ETH".to_string(),
+        side: Side::Buy,
+        reduce_only: false,
+        limit_px: 1800.0,
+        sz: 0.01,
+        cloid: None,
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+    };
+
+    let response = exchange_client
+        .order(order, None, Grouping::Na)
+        .await
+        .unwrap();
+    info!("Order placed with a boxed signer: {response:?}");
+}
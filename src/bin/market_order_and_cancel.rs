@@ -2,8 +2,8 @@ use alloy::signers::local::PrivateKeySigner;
 use log::info;
 
 use hyperliquid_rust_sdk::{
-    BaseUrl, ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, MarketCloseParams,
-    MarketOrderParams,
+    BaseUrl, ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, Grouping,
+    MarketCloseParams, MarketOrderParams, Side,
 };
 use std::{thread::sleep, time::Duration};
 
@@ -22,16 +22,17 @@ async fn main() {
     // Market open order
     let market_open_params = MarketOrderParams {
         asset: "ETH",
-        is_buy: true,
+        side: Side::Buy,
         sz: 0.01,
         px: None,
         slippage: Some(0.01), // 1% slippage
+        price_limit: None,
         cloid: None,
         wallet: None,
     };
 
     let response = exchange_client
-        .market_open(market_open_params)
+        .market_open(market_open_params, Grouping::Na)
         .await
         .unwrap();
     info!("Market open order placed: {response:?}");
@@ -61,7 +62,7 @@ async fn main() {
     };
 
     let response = exchange_client
-        .market_close(market_close_params)
+        .market_close(market_close_params, Grouping::Na)
         .await
         .unwrap();
     info!("Market close order placed: {response:?}");
@@ -2,8 +2,8 @@ use alloy::signers::local::PrivateKeySigner;
 use log::info;
 
 use hyperliquid_rust_sdk::{
-    BaseUrl, BuilderInfo, ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus,
-    MarketCloseParams, MarketOrderParams,
+    BaseUrl, BuilderInfo, ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, Grouping,
+    MarketCloseParams, MarketOrderParams, Side,
 };
 use std::{thread::sleep, time::Duration};
 
@@ -22,10 +22,11 @@ async fn main() {
     // Market open order
     let market_open_params = MarketOrderParams {
         asset: "ETH",
-        is_buy: true,
+        side: Side::Buy,
         sz: 0.01,
         px: None,
         slippage: Some(0.01), // 1% slippage
+        price_limit: None,
         cloid: None,
         wallet: None,
     };
@@ -40,6 +41,7 @@ async fn main() {
                 builder: builder.to_string(),
                 fee,
             },
+            Grouping::Na,
         )
         .await
         .unwrap();
@@ -70,7 +72,7 @@ async fn main() {
     };
 
     let response = exchange_client
-        .market_close(market_close_params)
+        .market_close(market_close_params, Grouping::Na)
         .await
         .unwrap();
     info!("Market close order placed: {response:?}");
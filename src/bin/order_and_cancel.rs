@@ -1,10 +1,9 @@
-
 use alloy::signers::local::PrivateKeySigner;
 use log::info;
 
 use hyperliquid_rust_sdk::{
     BaseUrl, ClientCancelRequest, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient,
-    ExchangeDataStatus, ExchangeResponseStatus,
+    ExchangeDataStatus, Grouping, Side, Tif,
 };
 use std::{thread::sleep, time::Duration};
 
@@ -12,9 +11,10 @@ use std::{thread::sleep, time::Duration};
 async fn main() {
     env_logger::init();
     // Key was randomly generated for testing and shouldn't be used with any real funds
-    let wallet: PrivateKeySigner = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
-        .parse()
-        .unwrap();
+    let wallet: PrivateKeySigner =
+        "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .unwrap();
 
     let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
         .await
@@ -22,23 +22,21 @@ async fn main() {
 
     let order = ClientOrderRequest {
         asset: "ETH".to_string(),
-        is_buy: true,
+        side: Side::Buy,
         reduce_only: false,
         limit_px: 1800.0,
         sz: 0.01,
         cloid: None,
-        order_type: ClientOrder::Limit(ClientLimit {
-            tif: "Gtc".to_string(),
-        }),
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
     };
 
-    let response = exchange_client.order(order, None).await.unwrap();
+    let response = exchange_client
+        .order(order, None, Grouping::Na)
+        .await
+        .unwrap();
     info!("Order placed: {response:?}");
 
-    let response = match response {
-        ExchangeResponseStatus::Ok(exchange_response) => exchange_response,
-        ExchangeResponseStatus::Err(e) => panic!("error with exchange response: {e}"),
-    };
+    let response = response.into_result().unwrap();
     let status = response.data.unwrap().statuses[0].clone();
     let oid = match status {
         ExchangeDataStatus::Filled(order) => order.oid,
@@ -3,7 +3,7 @@ use log::info;
 
 use hyperliquid_rust_sdk::{
     BaseUrl, ClientCancelRequestCloid, ClientLimit, ClientOrder, ClientOrderRequest, ClientTrigger,
-    ExchangeClient,
+    ExchangeClient, Grouping, Side, Tif,
 };
 use std::{thread::sleep, time::Duration};
 use uuid::Uuid;
@@ -25,19 +25,17 @@ async fn main() {
     let cloid = Uuid::new_v4();
     let order = ClientOrderRequest {
         asset: "ETH".to_string(),
-        is_buy: true,
+        side: Side::Buy,
         reduce_only: false,
         limit_px: 1800.0,
         sz: 1.0,
         cloid: Some(cloid),
-        order_type: ClientOrder::Limit(ClientLimit {
-            tif: "Gtc".to_string(),
-        }),
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
     };
 
     let take_profit = ClientOrderRequest {
         asset: "ETH".to_string(),
-        is_buy: false,
+        side: Side::Sell,
         reduce_only: true,
         limit_px: 1900.0,
         sz: 1.0,
@@ -50,7 +48,7 @@ async fn main() {
     };
 
     let response = exchange_client
-        .bulk_order(vec![order, take_profit], None, "normalTpsl".to_string())
+        .bulk_order(vec![order, take_profit], None, Grouping::NormalTpsl)
         .await
         .unwrap();
     info!("Order placed: {response:?}");
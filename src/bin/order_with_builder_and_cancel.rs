@@ -3,7 +3,7 @@ use log::info;
 
 use hyperliquid_rust_sdk::{
     BaseUrl, BuilderInfo, ClientCancelRequest, ClientLimit, ClientOrder, ClientOrderRequest,
-    ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus,
+    ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, Grouping, Side, Tif,
 };
 use std::{thread::sleep, time::Duration};
 
@@ -21,14 +21,12 @@ async fn main() {
 
     let order = ClientOrderRequest {
         asset: "ETH".to_string(),
-        is_buy: true,
+        side: Side::Buy,
         reduce_only: false,
         limit_px: 1800.0,
         sz: 0.01,
         cloid: None,
-        order_type: ClientOrder::Limit(ClientLimit {
-            tif: "Gtc".to_string(),
-        }),
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
     };
 
     let fee = 1u64;
@@ -42,6 +40,7 @@ async fn main() {
                 builder: builder.to_string(),
                 fee,
             },
+            Grouping::Na,
         )
         .await
         .unwrap();
@@ -3,7 +3,7 @@ use log::info;
 
 use hyperliquid_rust_sdk::{
     BaseUrl, ClientCancelRequest, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient,
-    ExchangeDataStatus, ExchangeResponseStatus,
+    ExchangeDataStatus, ExchangeResponseStatus, Grouping, Side, Tif,
 };
 use std::{thread::sleep, time::Duration};
 
@@ -21,17 +21,18 @@ async fn main() {
 
     let order = ClientOrderRequest {
         asset: "XYZTWO/USDC".to_string(),
-        is_buy: true,
+        side: Side::Buy,
         reduce_only: false,
         limit_px: 0.00002378,
         sz: 1000000.0,
         cloid: None,
-        order_type: ClientOrder::Limit(ClientLimit {
-            tif: "Gtc".to_string(),
-        }),
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
     };
 
-    let response = exchange_client.order(order, None).await.unwrap();
+    let response = exchange_client
+        .order(order, None, Grouping::Na)
+        .await
+        .unwrap();
     info!("Order placed: {response:?}");
 
     let response = match response {
@@ -10,7 +10,7 @@ use tokio::{
 #[tokio::main]
 async fn main() {
     env_logger::init();
-    let mut info_client = InfoClient::new(None, Some(BaseUrl::Mainnet)).await.unwrap();
+    let info_client = InfoClient::new(None, Some(BaseUrl::Mainnet)).await.unwrap();
 
     let (sender, mut receiver) = unbounded_channel();
     let subscription_id = info_client
@@ -11,7 +11,7 @@ use tokio::{
 async fn main() {
     env_logger::init();
 
-    let mut info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
+    let info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
 
     let (sender, mut receiver) = unbounded_channel();
     let subscription_id = info_client
@@ -13,7 +13,7 @@ use tokio::{
 #[tokio::main]
 async fn main() {
     env_logger::init();
-    let mut info_client = InfoClient::new(None, Some(BaseUrl::Mainnet)).await.unwrap();
+    let info_client = InfoClient::new(None, Some(BaseUrl::Mainnet)).await.unwrap();
     let user = Address::from_str("0x59cdE545242534eCc0b3067EbCDce64E2bA64df0").unwrap();
 
     let (sender, mut receiver) = unbounded_channel();
@@ -7,7 +7,7 @@ use tokio::{spawn, sync::mpsc::unbounded_channel, time::sleep};
 #[tokio::main]
 async fn main() {
     env_logger::init();
-    let mut info_client = InfoClient::new(None, Some(BaseUrl::Mainnet)).await.unwrap();
+    let info_client = InfoClient::new(None, Some(BaseUrl::Mainnet)).await.unwrap();
 
     let (sender, mut receiver) = unbounded_channel();
     let subscription_id = info_client
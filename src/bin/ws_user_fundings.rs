@@ -13,7 +13,7 @@ use tokio::{
 #[tokio::main]
 async fn main() {
     env_logger::init();
-    let mut info_client = InfoClient::new(None, Some(BaseUrl::Mainnet)).await.unwrap();
+    let info_client = InfoClient::new(None, Some(BaseUrl::Mainnet)).await.unwrap();
     let user = Address::from_str("0x6FD45EE91654730b67c4E6e67804cDEC31EcF38d").unwrap();
 
     let (sender, mut receiver) = unbounded_channel();
@@ -13,7 +13,7 @@ use tokio::{
 #[tokio::main]
 async fn main() {
     env_logger::init();
-    let mut info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
+    let info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
     let user = Address::from_str("0x0bd592152216b0d21175c8cf856b5be05f3a7c3e").unwrap();
 
     let (sender, mut receiver) = unbounded_channel();
@@ -3,3 +3,15 @@ pub static TESTNET_API_URL: &str = "https://api.hyperliquid-testnet.xyz";
 pub static LOCAL_API_URL: &str = "http://localhost:3001";
 pub const EPSILON: f64 = 1e-9;
 pub(crate) const INF_BPS: u16 = 10_001;
+
+// Prices may have at most 5 significant figures and (MAX_DECIMALS - szDecimals) decimal places
+pub(crate) const MAX_SIG_FIGS: u32 = 5;
+pub(crate) const PERP_MAX_DECIMALS: u32 = 6;
+pub(crate) const SPOT_MAX_DECIMALS: u32 = 8;
+
+// Deducted from every bridge withdrawal, in USDC
+pub(crate) const WITHDRAW_FEE_USD: f64 = 1.0;
+
+pub const CANDLE_INTERVALS: [&str; 14] = [
+    "1m", "3m", "5m", "15m", "30m", "1h", "2h", "4h", "8h", "12h", "1d", "3d", "1w", "1M",
+];
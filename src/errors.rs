@@ -1,3 +1,4 @@
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone)]
@@ -31,6 +32,8 @@ pub enum Error {
     Wallet(String),
     #[error("Websocket error: {0:?}")]
     Websocket(String),
+    #[error("Websocket connection failed: {0:?}")]
+    WebsocketConnect(String),
     #[error("Subscription not found")]
     SubscriptionNotFound,
     #[error("WS manager not instantiated")]
@@ -61,4 +64,43 @@ pub enum Error {
     SignatureFailure(String),
     #[error("Vault address not found")]
     VaultAddressNotFound,
+    #[error("Agent approval failed: {0:?}")]
+    AgentApproval(String),
+    #[error("Recording error: {0:?}")]
+    Recording(String),
+    #[error("Unsupported candle interval: {0:?}")]
+    InvalidCandleInterval(String),
+    #[error("Invalid token id: {0:?}")]
+    InvalidTokenId(String),
+    #[error("Invalid time in force {0:?}, expected Alo, Ioc or Gtc")]
+    InvalidTif(String),
+    #[error("Nonce too old: {0:?}")]
+    NonceTooOld(String),
+    #[error("Nonce already used: {0:?}")]
+    NonceDuplicate(String),
+    #[error("Price {px} is not a multiple of the tick size, nearest valid price is {nearest}")]
+    InvalidTickSize { px: f64, nearest: f64 },
+    #[error("Size {sz} is not a multiple of the lot size, nearest valid size is {nearest}")]
+    InvalidLotSize { sz: f64, nearest: f64 },
+    #[error("WS post error: {0:?}")]
+    WsPost(String),
+    #[error("Builder fee of {required} is not approved, approved max fee is {approved}")]
+    BuilderFeeNotApproved { required: u64, approved: u64 },
+    #[error("Withdrawal of {amount} USDC does not exceed the withdrawal fee of {fee} USDC")]
+    WithdrawBelowMinimum { amount: f64, fee: f64 },
+    #[error("Nothing to send, the batch is empty")]
+    EmptyBatch,
+    #[error("Rejected by the exchange: {0:?}")]
+    ExchangeRejected(String),
+    #[error("Invalid cloid {0:?}, expected 32 hex digits")]
+    InvalidCloid(String),
+    #[error("Invalid replay speed {0}, expected a positive number")]
+    InvalidReplaySpeed(f64),
+    #[error(
+        "Agents valid for {valid_for:?} cannot be rotated {rotate_before:?} before they expire"
+    )]
+    InvalidRotationInterval {
+        valid_for: Duration,
+        rotate_before: Duration,
+    },
 }
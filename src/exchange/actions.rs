@@ -1,7 +1,10 @@
 use std::str::FromStr;
 
 use crate::exchange::{cancel::CancelRequest, modify::ModifyRequest, order::OrderRequest};
-use alloy::primitives::{Address, U256};
+use alloy::{
+    primitives::{Address, B256, U256},
+    signers::Signature,
+};
 pub(crate) use ethers::{
     abi::{encode, ParamType, Tokenizable},
     types::{
@@ -14,7 +17,7 @@ pub(crate) use ethers::{
 };
 use serde::{Deserialize, Serialize};
 
-use super::{cancel::CancelRequestCloid, BuilderInfo};
+use super::{cancel::CancelRequestCloid, Actions, BuilderInfo};
 
 pub(crate) const HYPERLIQUID_EIP_PREFIX: &str = "HyperliquidTransaction:";
 
@@ -96,11 +99,24 @@ pub struct UpdateIsolatedMargin {
     pub ntli: i64,
 }
 
+/// How the orders of a bulk order relate to each other
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Grouping {
+    /// Independent orders
+    #[default]
+    Na,
+    /// TP/SL orders attached to the entry order before them, sized to its fill
+    NormalTpsl,
+    /// TP/SL orders sized to and following the whole position
+    PositionTpsl,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BulkOrder {
     pub orders: Vec<OrderRequest>,
-    pub grouping: String,
+    pub grouping: Grouping,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub builder: Option<BuilderInfo>,
 }
@@ -129,6 +145,7 @@ pub struct ApproveAgent {
     pub signature_chain_id: U256,
     pub hyperliquid_chain: String,
     pub agent_address: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub agent_name: Option<String>,
     pub nonce: u64,
 }
@@ -292,12 +309,243 @@ pub struct VaultTransfer {
     pub usd: String,
 }
 
+// The address is kept as a string so it is hashed the same way it is sent
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountUsdTransfer {
+    pub sub_account_user: String,
+    pub is_deposit: bool,
+    pub usd: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSubAccount {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountModify {
+    pub sub_account_user: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SendAsset {
+    pub hyperliquid_chain: String,
+    pub signature_chain_id: U256,
+    pub destination: String,
+    pub source_dex: String,
+    pub destination_dex: String,
+    pub token: String,
+    pub amount: String,
+    pub from_sub_account: String,
+    pub nonce: u64,
+}
+
+impl Eip712 for SendAsset {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(eip712::make_type_hash(
+            format!("{HYPERLIQUID_EIP_PREFIX}SendAsset"),
+            &[
+                ("hyperliquidChain".to_string(), ParamType::String),
+                ("destination".to_string(), ParamType::String),
+                ("sourceDex".to_string(), ParamType::String),
+                ("destinationDex".to_string(), ParamType::String),
+                ("token".to_string(), ParamType::String),
+                ("amount".to_string(), ParamType::String),
+                ("fromSubAccount".to_string(), ParamType::String),
+                ("nonce".to_string(), ParamType::Uint(64)),
+            ],
+        ))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            destination,
+            source_dex,
+            destination_dex,
+            token,
+            amount,
+            from_sub_account,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(destination.clone().into_token()),
+            encode_eip712_type(source_dex.clone().into_token()),
+            encode_eip712_type(destination_dex.clone().into_token()),
+            encode_eip712_type(token.clone().into_token()),
+            encode_eip712_type(amount.clone().into_token()),
+            encode_eip712_type(from_sub_account.clone().into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SetReferrer {
     pub code: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleCancel {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReserveRequestWeight {
+    pub weight: u64,
+}
+
+// Field order is part of the signed msgpack payload, keep it in sync with the exchange
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum PerpDeploy {
+    RegisterAsset(PerpDeployRegisterAsset),
+    SetOracle(PerpDeploySetOracle),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PerpDeployRegisterAsset {
+    pub max_gas: Option<u64>,
+    pub asset_request: PerpAssetRequest,
+    pub dex: String,
+    pub schema: Option<PerpDexSchema>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PerpAssetRequest {
+    pub coin: String,
+    pub sz_decimals: u32,
+    pub oracle_px: String,
+    pub margin_table_id: u32,
+    pub only_isolated: bool,
+}
+
+// Only given when registering the first asset of a new dex
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PerpDexSchema {
+    pub full_name: String,
+    pub collateral_token: u32,
+    pub oracle_updater: Option<String>,
+}
+
+// Like `PerpDeploy`, the field order is part of the signed payload
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum SpotDeploy {
+    RegisterToken2(SpotDeployRegisterToken),
+    UserGenesis(SpotDeployUserGenesis),
+    Genesis(SpotDeployGenesis),
+    RegisterSpot(SpotDeployRegisterSpot),
+    SetDeployerTradingFeeShare(SpotDeploySetDeployerTradingFeeShare),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotTokenSpec {
+    pub name: String,
+    pub sz_decimals: u32,
+    pub wei_decimals: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotDeployRegisterToken {
+    pub spec: SpotTokenSpec,
+    pub max_gas: u64,
+    pub full_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotDeployUserGenesis {
+    pub token: u32,
+    pub user_and_wei: Vec<(String, String)>,
+    pub existing_token_and_wei: Vec<(u32, String)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotDeployGenesis {
+    pub token: u32,
+    pub max_supply: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_hyperliquidity: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotDeployRegisterSpot {
+    pub tokens: [u32; 2],
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotDeploySetDeployerTradingFeeShare {
+    pub token: u32,
+    pub share: String,
+}
+
+// Prices are (coin, px) pairs sorted by coin
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PerpDeploySetOracle {
+    pub dex: String,
+    pub oracle_pxs: Vec<(String, String)>,
+    pub mark_pxs: Vec<Vec<(String, String)>>,
+    pub external_perp_pxs: Vec<(String, String)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TwapOrder {
+    #[serde(rename = "a")]
+    pub asset: u32,
+    #[serde(rename = "b")]
+    pub is_buy: bool,
+    #[serde(rename = "s")]
+    pub sz: String,
+    #[serde(rename = "r")]
+    pub reduce_only: bool,
+    #[serde(rename = "m")]
+    pub minutes: u64,
+    #[serde(rename = "t")]
+    pub randomize: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BulkTwapOrder {
+    pub twap: TwapOrder,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TwapCancel {
+    #[serde(rename = "a")]
+    pub asset: u32,
+    #[serde(rename = "t")]
+    pub twap_id: u64,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ApproveBuilderFee {
@@ -393,3 +641,267 @@ impl Eip712 for UsdClassTransfer {
         Ok(keccak256(encode(&items)))
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenDelegate {
+    pub signature_chain_id: U256,
+    pub hyperliquid_chain: String,
+    pub validator: Address,
+    pub wei: u64,
+    pub is_undelegate: bool,
+    pub nonce: u64,
+}
+
+impl Eip712 for TokenDelegate {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(eip712::make_type_hash(
+            format!("{HYPERLIQUID_EIP_PREFIX}TokenDelegate"),
+            &[
+                ("hyperliquidChain".to_string(), ParamType::String),
+                ("validator".to_string(), ParamType::Address),
+                ("wei".to_string(), ParamType::Uint(64)),
+                ("isUndelegate".to_string(), ParamType::Bool),
+                ("nonce".to_string(), ParamType::Uint(64)),
+            ],
+        ))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            validator,
+            wei,
+            is_undelegate,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(validator.into_token()),
+            encode_eip712_type(wei.into_token()),
+            encode_eip712_type(is_undelegate.into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CDeposit {
+    pub signature_chain_id: U256,
+    pub hyperliquid_chain: String,
+    pub wei: u64,
+    pub nonce: u64,
+}
+
+impl Eip712 for CDeposit {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(eip712::make_type_hash(
+            format!("{HYPERLIQUID_EIP_PREFIX}CDeposit"),
+            &[
+                ("hyperliquidChain".to_string(), ParamType::String),
+                ("wei".to_string(), ParamType::Uint(64)),
+                ("nonce".to_string(), ParamType::Uint(64)),
+            ],
+        ))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            wei,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(wei.into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CWithdraw {
+    pub signature_chain_id: U256,
+    pub hyperliquid_chain: String,
+    pub wei: u64,
+    pub nonce: u64,
+}
+
+impl Eip712 for CWithdraw {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(eip712::make_type_hash(
+            format!("{HYPERLIQUID_EIP_PREFIX}CWithdraw"),
+            &[
+                ("hyperliquidChain".to_string(), ParamType::String),
+                ("wei".to_string(), ParamType::Uint(64)),
+                ("nonce".to_string(), ParamType::Uint(64)),
+            ],
+        ))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            wei,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(wei.into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
+
+// `signers` is the JSON encoded authorized users and threshold, as signed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertToMultiSigUser {
+    pub signature_chain_id: U256,
+    pub hyperliquid_chain: String,
+    pub signers: String,
+    pub nonce: u64,
+}
+
+impl Eip712 for ConvertToMultiSigUser {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(eip712::make_type_hash(
+            format!("{HYPERLIQUID_EIP_PREFIX}ConvertToMultiSigUser"),
+            &[
+                ("hyperliquidChain".to_string(), ParamType::String),
+                ("signers".to_string(), ParamType::String),
+                ("nonce".to_string(), ParamType::Uint(64)),
+            ],
+        ))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            signers,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(signers.clone().into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigSignature {
+    pub r: String,
+    pub s: String,
+    pub v: u64,
+}
+
+impl From<&Signature> for MultiSigSignature {
+    fn from(signature: &Signature) -> MultiSigSignature {
+        MultiSigSignature {
+            r: format!("{:#x}", signature.r()),
+            s: format!("{:#x}", signature.s()),
+            v: u8::from(signature.recid()) as u64 + 27,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigPayload {
+    pub multi_sig_user: String,
+    pub outer_signer: String,
+    pub action: Box<Actions>,
+}
+
+// The chain id is kept as the hex string the exchange hashes
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSig {
+    pub signature_chain_id: String,
+    pub signatures: Vec<MultiSigSignature>,
+    pub payload: MultiSigPayload,
+}
+
+// Signed by the outer signer over the hash of a `MultiSig` action
+#[derive(Debug, Clone)]
+pub struct SendMultiSig {
+    pub signature_chain_id: U256,
+    pub hyperliquid_chain: String,
+    pub multi_sig_action_hash: B256,
+    pub nonce: u64,
+}
+
+impl Eip712 for SendMultiSig {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(eip712::make_type_hash(
+            format!("{HYPERLIQUID_EIP_PREFIX}SendMultiSig"),
+            &[
+                ("hyperliquidChain".to_string(), ParamType::String),
+                ("multiSigActionHash".to_string(), ParamType::FixedBytes(32)),
+                ("nonce".to_string(), ParamType::Uint(64)),
+            ],
+        ))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            multi_sig_action_hash,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(ethers::types::H256(multi_sig_action_hash.0).into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    helpers::now_timestamp_ms, info::info_client::InfoClient, prelude::*, req::HttpClient, Error,
+    ExchangeClient, ExchangeResponseStatus,
+};
+use alloy::signers::{local::PrivateKeySigner, Signer};
+use log::{error, info};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{spawn, sync::RwLock, task::JoinHandle, time};
+
+/// An agent client that is periodically replaced by a freshly approved agent before the current one expires.
+#[derive(Debug)]
+pub struct RotatingAgent {
+    current: Arc<RwLock<Arc<ExchangeClient<PrivateKeySigner>>>>,
+    rotation_task: JoinHandle<()>,
+}
+
+impl RotatingAgent {
+    const RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+    pub async fn client(&self) -> Arc<ExchangeClient<PrivateKeySigner>> {
+        self.current.read().await.clone()
+    }
+}
+
+// Aborting the task also releases its handle on the master client right away
+impl Drop for RotatingAgent {
+    fn drop(&mut self) {
+        self.rotation_task.abort();
+    }
+}
+
+impl<T: Signer> ExchangeClient<T> {
+    // The exchange expires agents whose name carries a `valid_until <timestamp ms>` suffix
+    pub async fn approve_agent_until(
+        &self,
+        agent_name: &str,
+        valid_until: u64,
+        wallet: Option<&T>,
+    ) -> Result<(String, ExchangeResponseStatus)> {
+        self.approve_named_agent(
+            Some(format!("{agent_name} valid_until {valid_until}")),
+            wallet,
+        )
+        .await
+    }
+
+    pub async fn agent_client(
+        &self,
+        agent_name: &str,
+        valid_for: Duration,
+    ) -> Result<ExchangeClient<PrivateKeySigner>> {
+        let valid_until = now_timestamp_ms() + valid_for.as_millis() as u64;
+        let (private_key, response) = self
+            .approve_agent_until(agent_name, valid_until, None)
+            .await?;
+        if let ExchangeResponseStatus::Err(e) = response {
+            return Err(Error::AgentApproval(e));
+        }
+        let agent: PrivateKeySigner =
+            private_key
+                .parse()
+                .map_err(|e: alloy::signers::local::LocalSignerError| {
+                    Error::PrivateKeyParse(e.to_string())
+                })?;
+        Ok(self.with_signer(agent))
+    }
+
+    pub(crate) fn with_signer<S: Signer>(&self, wallet: S) -> ExchangeClient<S> {
+        let http_client = HttpClient::new(
+            self.http_client.client.clone(),
+            self.http_client.base_url.clone(),
+        )
+        .with_settings_of(&self.http_client);
+        ExchangeClient {
+            info_client: InfoClient::from_http_client(http_client.clone()),
+            http_client,
+            wallet,
+            meta: self.meta.clone(),
+            vault_address: self.vault_address,
+            coin_to_asset: self.coin_to_asset.clone(),
+            coin_to_sz_decimals: self.coin_to_sz_decimals.clone(),
+            spot_pairs: self.spot_pairs.clone(),
+            auto_retry: self.auto_retry,
+            // Agents are approved by the master wallet, which also approves builder fees
+            account_address: self.account_address.or(Some(self.wallet.address())),
+            approved_builder_fees: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T: Signer + Send + Sync + 'static> ExchangeClient<T> {
+    // The master wallet is only used to approve agents; trading goes through `RotatingAgent::client`.
+    // `rotate_before` must be shorter than `valid_for`, or agents would be approved back to back
+    pub async fn rotating_agent(
+        self: Arc<Self>,
+        agent_name: String,
+        valid_for: Duration,
+        rotate_before: Duration,
+    ) -> Result<RotatingAgent> {
+        if rotate_before >= valid_for {
+            return Err(Error::InvalidRotationInterval {
+                valid_for,
+                rotate_before,
+            });
+        }
+        let client = Arc::new(self.agent_client(&agent_name, valid_for).await?);
+        let current = Arc::new(RwLock::new(client));
+
+        let rotation_task = {
+            let current = Arc::clone(&current);
+            let rotate_every = valid_for - rotate_before;
+            let rotation_fut = async move {
+                let mut next_rotation = rotate_every;
+                loop {
+                    time::sleep(next_rotation).await;
+                    match self.agent_client(&agent_name, valid_for).await {
+                        Ok(client) => {
+                            info!("Rotated agent to {}", client.wallet.address());
+                            *current.write().await = Arc::new(client);
+                            next_rotation = rotate_every;
+                        }
+                        Err(err) => {
+                            error!("Could not rotate agent: {err}");
+                            next_rotation = RotatingAgent::RETRY_INTERVAL;
+                        }
+                    }
+                }
+            };
+            spawn(rotation_fut)
+        };
+
+        Ok(RotatingAgent {
+            current,
+            rotation_task,
+        })
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{
+    prelude::*, ClientCancelRequest, ClientCancelRequestCloid, ClientModifyRequest,
+    ClientOrderRequest, ExchangeClient, ExchangeResponseStatus, Grouping,
+};
+use alloy::signers::Signer;
+
+#[derive(Debug)]
+enum BatchAction {
+    UpdateLeverage {
+        leverage: u32,
+        coin: String,
+        is_cross: bool,
+    },
+    UpdateIsolatedMargin {
+        amount: f64,
+        coin: String,
+    },
+    Orders {
+        orders: Vec<ClientOrderRequest>,
+        grouping: Grouping,
+    },
+    Cancels(Vec<ClientCancelRequest>),
+    CancelsByCloid(Vec<ClientCancelRequestCloid>),
+    Modifies(Vec<ClientModifyRequest>),
+}
+
+/// Actions sent one after another by `ExchangeClient::send_batch`.
+/// The exchange only accepts one action per request, so each action gets its own increasing nonce
+/// and a batch is not atomic.
+#[derive(Debug, Default)]
+pub struct ActionBatch {
+    actions: Vec<BatchAction>,
+    stop_on_order_errors: bool,
+}
+
+impl ActionBatch {
+    pub fn new() -> ActionBatch {
+        ActionBatch::default()
+    }
+
+    pub fn update_leverage(mut self, leverage: u32, coin: &str, is_cross: bool) -> Self {
+        self.actions.push(BatchAction::UpdateLeverage {
+            leverage,
+            coin: coin.to_string(),
+            is_cross,
+        });
+        self
+    }
+
+    pub fn update_isolated_margin(mut self, amount: f64, coin: &str) -> Self {
+        self.actions.push(BatchAction::UpdateIsolatedMargin {
+            amount,
+            coin: coin.to_string(),
+        });
+        self
+    }
+
+    // Empty order, cancel and modify lists are skipped instead of being sent
+    pub fn orders(mut self, orders: Vec<ClientOrderRequest>, grouping: Grouping) -> Self {
+        if !orders.is_empty() {
+            self.actions.push(BatchAction::Orders { orders, grouping });
+        }
+        self
+    }
+
+    pub fn cancels(mut self, cancels: Vec<ClientCancelRequest>) -> Self {
+        if !cancels.is_empty() {
+            self.actions.push(BatchAction::Cancels(cancels));
+        }
+        self
+    }
+
+    pub fn cancels_by_cloid(mut self, cancels: Vec<ClientCancelRequestCloid>) -> Self {
+        if !cancels.is_empty() {
+            self.actions.push(BatchAction::CancelsByCloid(cancels));
+        }
+        self
+    }
+
+    pub fn modifies(mut self, modifies: Vec<ClientModifyRequest>) -> Self {
+        if !modifies.is_empty() {
+            self.actions.push(BatchAction::Modifies(modifies));
+        }
+        self
+    }
+
+    // By default only a failed request or an action rejected as a whole stops the batch, with this
+    // a single rejected order, cancel or modify in a response stops it too
+    pub fn stop_on_order_errors(mut self) -> Self {
+        self.stop_on_order_errors = true;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+}
+
+impl<T: Signer> ExchangeClient<T> {
+    // Stops at the first failed action, so later actions never run on top of it. There is one
+    // result per action that was sent, ending with the failure, so the actions before it can be
+    // told apart from those that never ran
+    pub async fn send_batch(
+        &self,
+        batch: ActionBatch,
+        wallet: Option<&T>,
+    ) -> Vec<Result<ExchangeResponseStatus>> {
+        let mut responses = Vec::with_capacity(batch.len());
+        for action in batch.actions {
+            let response = match action {
+                BatchAction::UpdateLeverage {
+                    leverage,
+                    coin,
+                    is_cross,
+                } => {
+                    self.update_leverage(leverage, &coin, is_cross, wallet)
+                        .await
+                }
+                BatchAction::UpdateIsolatedMargin { amount, coin } => {
+                    self.update_isolated_margin(amount, &coin, wallet).await
+                }
+                BatchAction::Orders { orders, grouping } => {
+                    self.bulk_order(orders, wallet, grouping).await
+                }
+                BatchAction::Cancels(cancels) => self.bulk_cancel(cancels, wallet).await,
+                BatchAction::CancelsByCloid(cancels) => {
+                    self.bulk_cancel_by_cloid(cancels, wallet).await
+                }
+                BatchAction::Modifies(modifies) => self.bulk_modify(modifies, wallet).await,
+            };
+            let rejected = match &response {
+                Ok(ExchangeResponseStatus::Err(_)) | Err(_) => true,
+                Ok(response) => batch.stop_on_order_errors && !response.is_success(),
+            };
+            responses.push(response);
+            if rejected {
+                break;
+            }
+        }
+        responses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_action_lists_are_skipped() {
+        let batch = ActionBatch::new()
+            .orders(vec![], Grouping::Na)
+            .cancels(vec![])
+            .update_leverage(5, "ETH", true)
+            .modifies(vec![]);
+        assert_eq!(batch.len(), 1);
+        assert!(ActionBatch::new().cancels_by_cloid(vec![]).is_empty());
+    }
+}
@@ -1,41 +1,76 @@
 use crate::signature::sign_typed_data;
 use crate::{
+    consts::{MAX_SIG_FIGS, PERP_MAX_DECIMALS, SPOT_MAX_DECIMALS, WITHDRAW_FEE_USD},
     exchange::{
         actions::{
-            ApproveAgent, ApproveBuilderFee, BulkCancel, BulkModify, BulkOrder, SetReferrer,
-            UpdateIsolatedMargin, UpdateLeverage, UsdSend,
+            ApproveAgent, ApproveBuilderFee, BulkCancel, BulkModify, BulkOrder, BulkTwapOrder,
+            CDeposit, CWithdraw, ConvertToMultiSigUser, CreateSubAccount, MultiSig,
+            MultiSigPayload, MultiSigSignature, PerpAssetRequest, PerpDeploy,
+            PerpDeployRegisterAsset, PerpDeploySetOracle, PerpDexSchema, ReserveRequestWeight,
+            ScheduleCancel, SendAsset, SendMultiSig, SetReferrer, SpotDeploy, SpotDeployGenesis,
+            SpotDeployRegisterSpot, SpotDeployRegisterToken, SpotDeploySetDeployerTradingFeeShare,
+            SpotDeployUserGenesis, SpotTokenSpec, SubAccountModify, SubAccountUsdTransfer,
+            TokenDelegate, TwapCancel, TwapOrder, UpdateIsolatedMargin, UpdateLeverage, UsdSend,
         },
         cancel::{CancelRequest, CancelRequestCloid},
+        exchange_responses::nonce_error,
         modify::{ClientModifyRequest, ModifyRequest},
-        ClientCancelRequest, ClientOrderRequest,
+        ClientCancelRequest, ClientOrderRequest, Side,
+    },
+    helpers::{
+        float_to_string_for_hashing, generate_random_key, next_nonce, round_to_decimals,
+        round_to_significant_and_decimal, round_to_significant_and_decimal_directed,
+        truncate_float, uuid_to_hex_string, Amount,
     },
-    helpers::{generate_random_key, next_nonce, uuid_to_hex_string},
     info::info_client::InfoClient,
-    meta::Meta,
+    meta::{Meta, SpotMeta},
     prelude::*,
-    req::HttpClient,
+    req::{HttpClient, RateLimit, RetryPolicy},
     signature::sign_l1_action,
-    BaseUrl, BulkCancelCloid, Error, ExchangeResponseStatus,
+    BaseUrl, BulkCancelCloid, Error, ExchangeDataStatus, ExchangeDataStatuses, ExchangeError,
+    ExchangeResponse, ExchangeResponseStatus, FrontendOpenOrdersResponse, Message, OrderWaitStatus,
+    Subscription,
 };
 use crate::{ClassTransfer, SpotSend, SpotUser, VaultTransfer, Withdraw3};
 use alloy::primitives::{keccak256, Address, B256, U256};
-use alloy::signers::{Signature, Signer};
-use log::{debug, info};
+use alloy::signers::{local::PrivateKeySigner, Signature, Signer};
+use log::{debug, info, warn};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::mpsc::{unbounded_channel, UnboundedReceiver},
+    time,
+};
+use uuid::Uuid;
 
 use super::cancel::ClientCancelRequestCloid;
-use super::order::{MarketCloseParams, MarketOrderParams};
-use super::{BuilderInfo, ClientLimit, ClientOrder, UsdClassTransfer};
+use super::order::{MarketCloseParams, MarketOrderParams, PositionTpslParams, TwapOrderParams};
+use super::{
+    BuilderInfo, ClientLimit, ClientOrder, ClientTrigger, Grouping, Tif, UsdClassTransfer,
+};
 
+// `T` can be any alloy signer, including `Box<dyn Signer + Send + Sync>` for hardware or remote signers
 #[derive(Debug)]
 pub struct ExchangeClient<T: Signer> {
     pub http_client: HttpClient,
+    // Shares `http_client`'s settings, and one websocket connection for order updates
+    pub(crate) info_client: InfoClient,
     pub wallet: T,
     pub meta: Meta,
     pub vault_address: Option<Address>,
     pub coin_to_asset: HashMap<String, u32>,
+    pub(crate) coin_to_sz_decimals: HashMap<String, u32>,
+    // Pair name, which keys its mid, and size decimals of the base token, per spot asset
+    pub(crate) spot_pairs: HashMap<u32, (String, u32)>,
+    pub(crate) auto_retry: bool,
+    pub(crate) account_address: Option<Address>,
+    // Max builder fee approved by the account, per builder
+    pub(crate) approved_builder_fees: Mutex<HashMap<Address, u64>>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -67,20 +102,65 @@ pub enum Actions {
     SetReferrer(SetReferrer),
     ApproveBuilderFee(ApproveBuilderFee),
     UsdClassTransfer(UsdClassTransfer),
+    Noop,
+    ScheduleCancel(ScheduleCancel),
+    TwapOrder(BulkTwapOrder),
+    TwapCancel(TwapCancel),
+    SubAccountTransfer(SubAccountUsdTransfer),
+    CreateSubAccount(CreateSubAccount),
+    SubAccountModify(SubAccountModify),
+    TokenDelegate(TokenDelegate),
+    CDeposit(CDeposit),
+    CWithdraw(CWithdraw),
+    MultiSig(MultiSig),
+    ConvertToMultiSigUser(ConvertToMultiSigUser),
+    ReserveRequestWeight(ReserveRequestWeight),
+    PerpDeploy(PerpDeploy),
+    SpotDeploy(SpotDeploy),
+    SendAsset(SendAsset),
 }
 
 impl Actions {
     fn hash(&self, timestamp: u64, vault_address: Option<Address>) -> Result<B256> {
-        let mut bytes =
-            rmp_serde::to_vec_named(self).map_err(|e| Error::RmpParse(e.to_string()))?;
-        bytes.extend(timestamp.to_be_bytes());
-        if let Some(vault_address) = vault_address {
-            bytes.push(1);
-            bytes.extend(vault_address.0);
-        } else {
-            bytes.push(0);
+        hash_action(self, timestamp, vault_address)
+    }
+}
+
+fn hash_action<A: Serialize>(
+    action: &A,
+    timestamp: u64,
+    vault_address: Option<Address>,
+) -> Result<B256> {
+    debug!("signing timestamp {timestamp} vault_address {vault_address:?}");
+    let mut bytes = rmp_serde::to_vec_named(action).map_err(|e| Error::RmpParse(e.to_string()))?;
+    bytes.extend(timestamp.to_be_bytes());
+    if let Some(vault_address) = vault_address {
+        bytes.push(1);
+        bytes.extend(vault_address.0);
+    } else {
+        bytes.push(0);
+    }
+    Ok(B256::from(keccak256(bytes)))
+}
+
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(1);
+
+// Only failures where the exchange rejected the whole request are retried: a rejected nonce
+// (`Error::NonceTooOld`, `Error::NonceDuplicate`) at once, HTTP 429 and a "too many requests"
+// response after `RATE_LIMIT_BACKOFF`. Rejections of single orders, e.g. insufficient margin,
+// are never retried
+fn retry_delay(response: &Result<ExchangeResponseStatus>) -> Option<Duration> {
+    match response {
+        Err(Error::NonceTooOld(_) | Error::NonceDuplicate(_)) => Some(Duration::ZERO),
+        Err(Error::ClientRequest { status_code, .. }) if *status_code == 429 => {
+            Some(RATE_LIMIT_BACKOFF)
         }
-        Ok(B256::from(keccak256(bytes)))
+        Ok(ExchangeResponseStatus::Err(message))
+            if ExchangeError::from(message.as_str()) == ExchangeError::RateLimited =>
+        {
+            Some(RATE_LIMIT_BACKOFF)
+        }
+        _ => None,
     }
 }
 
@@ -95,33 +175,124 @@ impl<T: Signer> ExchangeClient<T> {
         let client = client.unwrap_or_default();
         let base_url = base_url.unwrap_or(BaseUrl::Mainnet);
 
-        let info = InfoClient::new(None, Some(base_url)).await?;
+        let http_client = HttpClient::new(client, base_url.get_url());
+        let info_client = InfoClient::from_http_client(http_client.clone());
         let meta = if let Some(meta) = meta {
             meta
         } else {
-            info.meta().await?
+            info_client.meta().await?
         };
+        let spot_meta = info_client.spot_meta().await?;
+
+        Ok(ExchangeClient {
+            wallet,
+            coin_to_asset: Self::coin_to_asset(&meta, &spot_meta),
+            coin_to_sz_decimals: Self::coin_to_sz_decimals(&meta),
+            spot_pairs: Self::spot_pairs(&spot_meta),
+            meta,
+            vault_address,
+            http_client,
+            info_client,
+            auto_retry: false,
+            account_address: None,
+            approved_builder_fees: Mutex::new(HashMap::new()),
+        })
+    }
+
+    // Orders, cancels and modifies rejected for their nonce or rate limiting are resent once with a
+    // fresh nonce, see `retry_delay` for exactly which failures
+    pub fn with_auto_retry(mut self) -> ExchangeClient<T> {
+        self.auto_retry = true;
+        self
+    }
+
+    // The master account that approved builder fees when `wallet` is an agent, clients made by
+    // `agent_client` already know it
+    pub fn with_account_address(mut self, account_address: Address) -> ExchangeClient<T> {
+        self.account_address = Some(account_address);
+        self
+    }
 
+    fn coin_to_sz_decimals(meta: &Meta) -> HashMap<String, u32> {
+        meta.universe
+            .iter()
+            .map(|asset| (asset.name.clone(), asset.sz_decimals))
+            .collect()
+    }
+
+    // Size decimals of a perp asset in the cached meta
+    pub fn sz_decimals(&self, coin: &str) -> Option<u32> {
+        self.coin_to_sz_decimals.get(coin).copied()
+    }
+
+    fn coin_to_asset(meta: &Meta, spot_meta: &SpotMeta) -> HashMap<String, u32> {
         let mut coin_to_asset = HashMap::new();
         for (asset_ind, asset) in meta.universe.iter().enumerate() {
             coin_to_asset.insert(asset.name.clone(), asset_ind as u32);
         }
 
-        coin_to_asset = info
-            .spot_meta()
-            .await?
-            .add_pair_and_name_to_index_map(coin_to_asset);
+        spot_meta.add_pair_and_name_to_index_map(coin_to_asset)
+    }
 
-        Ok(ExchangeClient {
-            wallet,
-            meta,
-            vault_address,
-            http_client: HttpClient {
-                client,
-                base_url: base_url.get_url(),
-            },
-            coin_to_asset,
-        })
+    // A pair takes its size decimals from its base token
+    fn spot_pairs(spot_meta: &SpotMeta) -> HashMap<u32, (String, u32)> {
+        spot_meta
+            .universe
+            .iter()
+            .filter_map(|pair| {
+                let (_, base_token) = spot_meta.pair(pair.index as u32)?;
+                Some((
+                    10000 + pair.index as u32,
+                    (pair.name.clone(), base_token.sz_decimals as u32),
+                ))
+            })
+            .collect()
+    }
+
+    // Meta is fetched once on construction, refresh it to pick up newly listed assets
+    pub async fn refresh_meta(&mut self) -> Result<()> {
+        let meta = self.info_client.meta().await?;
+        let spot_meta = self.info_client.spot_meta().await?;
+        self.coin_to_asset = Self::coin_to_asset(&meta, &spot_meta);
+        self.coin_to_sz_decimals = Self::coin_to_sz_decimals(&meta);
+        self.spot_pairs = Self::spot_pairs(&spot_meta);
+        self.meta = meta;
+        Ok(())
+    }
+
+    // The connection id signed for an L1 action sent with `nonce` by this client
+    pub fn action_hash(&self, action: &Actions, nonce: u64) -> Result<B256> {
+        action.hash(nonce, self.vault_address)
+    }
+
+    // Chain id of the EIP-712 domain for user signed actions: Arbitrum One on mainnet, Arbitrum
+    // Sepolia on testnet
+    fn signature_chain_id(&self) -> U256 {
+        if self.http_client.is_mainnet() {
+            U256::from(0xa4b1)
+        } else {
+            U256::from(0x66eee)
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> ExchangeClient<T> {
+        self.http_client = self.http_client.with_timeout(timeout);
+        self.info_client.http_client = self.http_client.clone();
+        self
+    }
+
+    // Only applies to the info queries made on the way, exchange actions are never retried
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> ExchangeClient<T> {
+        self.http_client = self.http_client.with_retry_policy(retry_policy);
+        self.info_client.http_client = self.http_client.clone();
+        self
+    }
+
+    // Off by default, requests are then sent as soon as they are made
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> ExchangeClient<T> {
+        self.http_client = self.http_client.with_rate_limit(rate_limit);
+        self.info_client.http_client = self.http_client.clone();
+        self
     }
 
     async fn post(
@@ -141,15 +312,32 @@ impl<T: Signer> ExchangeClient<T> {
             nonce,
             vault_address: self.vault_address,
         };
+        let action_type = exchange_payload.action["type"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
         let res = serde_json::to_string(&exchange_payload)
             .map_err(|e| Error::JsonParse(e.to_string()))?;
-        let output = &self.http_client.post("/exchange", res).await.unwrap();
-        serde_json::from_str(output).map_err(|e| Error::JsonParse(e.to_string()))
+        let start = Instant::now();
+        let output = self.http_client.post("/exchange", res).await;
+        debug!(
+            "POST /exchange action {action_type} nonce {nonce} took {:?}",
+            start.elapsed()
+        );
+        let output = &output?;
+        let response: ExchangeResponseStatus =
+            serde_json::from_str(output).map_err(|e| Error::JsonParse(e.to_string()))?;
+        if let ExchangeResponseStatus::Err(message) = &response {
+            if let Some(err) = nonce_error(message) {
+                return Err(err);
+            }
+        }
+        Ok(response)
     }
 
     pub async fn usdc_transfer(
         &self,
-        amount: &str,
+        amount: impl Into<Amount>,
         destination: &str,
         wallet: Option<&T>,
     ) -> Result<ExchangeResponseStatus> {
@@ -162,10 +350,10 @@ impl<T: Signer> ExchangeClient<T> {
 
         let timestamp = next_nonce();
         let usd_send = UsdSend {
-            signature_chain_id: U256::from(421614),
+            signature_chain_id: self.signature_chain_id(),
             hyperliquid_chain,
             destination: destination.to_string(),
-            amount: amount.to_string(),
+            amount: amount.into().to_string(),
             time: timestamp,
         };
         let signature = sign_typed_data(&usd_send, wallet).await?;
@@ -177,12 +365,12 @@ impl<T: Signer> ExchangeClient<T> {
 
     pub async fn class_transfer(
         &self,
-        usdc: f64,
+        usdc: impl Into<Amount>,
         to_perp: bool,
         wallet: Option<&T>,
     ) -> Result<ExchangeResponseStatus> {
         // payload expects usdc without decimals
-        let usdc = (usdc * 1e6).round() as u64;
+        let usdc = usdc.into().to_micro_usdc()? as u64;
         let wallet = wallet.unwrap_or(&self.wallet);
 
         let timestamp = next_nonce();
@@ -199,7 +387,7 @@ impl<T: Signer> ExchangeClient<T> {
 
     pub async fn transfer_usd_to_spot(
         &self,
-        usdc: String,
+        usdc: impl Into<Amount>,
         wallet: Option<&T>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
@@ -210,8 +398,8 @@ impl<T: Signer> ExchangeClient<T> {
             } else {
                 "Testnet".to_string()
             },
-            signature_chain_id: U256::from(0xa4b1),
-            amount: usdc,
+            signature_chain_id: self.signature_chain_id(),
+            amount: usdc.into().to_string(),
             to_perp: false,
             nonce: timestamp,
         };
@@ -223,7 +411,7 @@ impl<T: Signer> ExchangeClient<T> {
 
     pub async fn transfer_usd_to_perp(
         &self,
-        usdc: String,
+        usdc: impl Into<Amount>,
         wallet: Option<&T>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
@@ -234,8 +422,8 @@ impl<T: Signer> ExchangeClient<T> {
             } else {
                 "Testnet".to_string()
             },
-            signature_chain_id: U256::from(0xa4b1),
-            amount: usdc,
+            signature_chain_id: self.signature_chain_id(),
+            amount: usdc.into().to_string(),
             to_perp: true,
             nonce: timestamp,
         };
@@ -260,7 +448,7 @@ impl<T: Signer> ExchangeClient<T> {
             "Testnet".to_string()
         };
         let approve_builder_fee: ApproveBuilderFee = ApproveBuilderFee {
-            signature_chain_id: U256::from(421614),
+            signature_chain_id: self.signature_chain_id(),
             hyperliquid_chain,
             builder,
             max_fee_rate,
@@ -300,84 +488,195 @@ impl<T: Signer> ExchangeClient<T> {
         self.post(action, signature, timestamp).await
     }
 
+    // The new sub account address is read with `ExchangeResponseStatus::sub_account_address`
+    pub async fn create_sub_account(
+        &self,
+        name: String,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = Actions::CreateSubAccount(CreateSubAccount { name });
+        self.post_l1_action(&action, wallet).await
+    }
+
+    pub async fn modify_sub_account_name(
+        &self,
+        sub_account_user: Address,
+        name: String,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = Actions::SubAccountModify(SubAccountModify {
+            sub_account_user: sub_account_user.to_string().to_lowercase(),
+            name,
+        });
+        self.post_l1_action(&action, wallet).await
+    }
+
+    // `usd` is in micro USDC, so 1_000_000 moves 1 USDC
+    pub async fn sub_account_transfer(
+        &self,
+        sub_account_user: Address,
+        is_deposit: bool,
+        usd: u64,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = Actions::SubAccountTransfer(SubAccountUsdTransfer {
+            sub_account_user: sub_account_user.to_string().to_lowercase(),
+            is_deposit,
+            usd,
+        });
+        self.post_l1_action(&action, wallet).await
+    }
+
+    async fn market_open_px(&self, params: &MarketOrderParams<'_, T>) -> Result<(f64, u32)> {
+        if let Some(price_limit) = params.price_limit {
+            // Round towards the mid so the fill is never worse than the limit
+            let (sz_decimals, price_decimals) = self.asset_decimals(params.asset)?;
+            let px = round_to_significant_and_decimal_directed(
+                price_limit,
+                MAX_SIG_FIGS,
+                price_decimals,
+                !params.side.is_buy(),
+            );
+            return Ok((px, sz_decimals));
+        }
+        let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
+        self.calculate_slippage_price(params.asset, params.side, slippage, params.px)
+            .await
+    }
+
     pub async fn market_open(
         &self,
         params: MarketOrderParams<'_, T>,
-        grouping: String,
+        grouping: Grouping,
     ) -> Result<ExchangeResponseStatus> {
-        let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
-        let (px, sz_decimals) = self
-            .calculate_slippage_price(params.asset, params.is_buy, slippage, params.px)
-            .await?;
+        let (px, sz_decimals) = self.market_open_px(&params).await?;
 
         let order = ClientOrderRequest {
             asset: params.asset.to_string(),
-            is_buy: params.is_buy,
+            side: params.side,
             reduce_only: false,
             limit_px: px,
             sz: round_to_decimals(params.sz, sz_decimals),
             cloid: params.cloid,
-            order_type: ClientOrder::Limit(ClientLimit {
-                tif: "Ioc".to_string(),
-            }),
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Ioc }),
         };
 
         self.order(order, params.wallet, grouping).await
     }
 
+    // Spot pairs are named "BASE/QUOTE", e.g. "PURR/USDC", and priced with up to 8 decimals
+    // minus the size decimals of the base token
+    pub async fn spot_market_open(
+        &self,
+        params: MarketOrderParams<'_, T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let (pair_name, sz_decimals) = self
+            .coin_to_asset
+            .get(params.asset)
+            .and_then(|asset| self.spot_pairs.get(asset))
+            .ok_or(Error::AssetNotFound)?;
+        let sz_decimals = *sz_decimals;
+        let price_decimals = SPOT_MAX_DECIMALS.saturating_sub(sz_decimals);
+
+        let px = if let Some(price_limit) = params.price_limit {
+            round_to_significant_and_decimal_directed(
+                price_limit,
+                MAX_SIG_FIGS,
+                price_decimals,
+                !params.side.is_buy(),
+            )
+        } else {
+            // Mids of non canonical pairs are keyed by their "@<index>" name
+            let px = match params.px {
+                Some(px) => px,
+                None => self
+                    .info_client
+                    .all_mids_for_dex(None)
+                    .await?
+                    .get_f64(pair_name)
+                    .ok_or(Error::AssetNotFound)?,
+            };
+            let slippage = params.slippage.unwrap_or(0.05);
+            Self::slippage_px(px, params.side.is_buy(), slippage, price_decimals)
+        };
+
+        let order = ClientOrderRequest {
+            asset: params.asset.to_string(),
+            side: params.side,
+            reduce_only: false,
+            limit_px: px,
+            sz: round_to_decimals(params.sz, sz_decimals),
+            cloid: params.cloid,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Ioc }),
+        };
+
+        self.order(order, params.wallet, Grouping::Na).await
+    }
+
+    pub async fn twap_order(
+        &self,
+        params: TwapOrderParams<'_>,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let &asset = self
+            .coin_to_asset
+            .get(params.asset)
+            .ok_or(Error::AssetNotFound)?;
+        let action = Actions::TwapOrder(BulkTwapOrder {
+            twap: TwapOrder {
+                asset,
+                is_buy: params.side.is_buy(),
+                sz: float_to_string_for_hashing(params.sz),
+                reduce_only: params.reduce_only,
+                minutes: params.minutes,
+                randomize: params.randomize,
+            },
+        });
+        self.post_l1_action(&action, wallet).await
+    }
+
+    pub async fn twap_cancel(
+        &self,
+        asset: &str,
+        twap_id: u64,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let &asset = self.coin_to_asset.get(asset).ok_or(Error::AssetNotFound)?;
+        let action = Actions::TwapCancel(TwapCancel { asset, twap_id });
+        self.post_l1_action(&action, wallet).await
+    }
+
     pub async fn market_open_with_builder(
         &self,
         params: MarketOrderParams<'_, T>,
         builder: BuilderInfo,
-        grouping: String,
+        grouping: Grouping,
     ) -> Result<ExchangeResponseStatus> {
-        let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
-        let (px, sz_decimals) = self
-            .calculate_slippage_price(params.asset, params.is_buy, slippage, params.px)
-            .await?;
+        let (px, sz_decimals) = self.market_open_px(&params).await?;
 
         let order = ClientOrderRequest {
             asset: params.asset.to_string(),
-            is_buy: params.is_buy,
+            side: params.side,
             reduce_only: false,
             limit_px: px,
             sz: round_to_decimals(params.sz, sz_decimals),
             cloid: params.cloid,
-            order_type: ClientOrder::Limit(ClientLimit {
-                tif: "Ioc".to_string(),
-            }),
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Ioc }),
         };
 
-        self.order_with_builder(order, params.wallet, builder, grouping).await
+        self.order_with_builder(order, params.wallet, builder, grouping)
+            .await
     }
 
     pub async fn market_close(
         &self,
         params: MarketCloseParams<'_, T>,
-        grouping: String,
+        grouping: Grouping,
     ) -> Result<ExchangeResponseStatus> {
         let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
         let wallet = params.wallet.unwrap_or(&self.wallet);
 
-        let base_url = match self.http_client.base_url.as_str() {
-            "https://api.hyperliquid.xyz" => BaseUrl::Mainnet,
-            "https://api.hyperliquid-testnet.xyz" => BaseUrl::Testnet,
-            _ => return Err(Error::GenericRequest("Invalid base URL".to_string())),
-        };
-        let info_client = InfoClient::new(None, Some(base_url)).await?;
-        let user_state = info_client.user_state(wallet.address()).await?;
-
-        let position = user_state
-            .asset_positions
-            .iter()
-            .find(|p| p.position.coin == params.asset)
-            .ok_or(Error::AssetNotFound)?;
-
-        let szi = position
-            .position
-            .szi
-            .parse::<f64>()
-            .map_err(|_| Error::FloatStringParse)?;
+        let szi = self.position_szi(params.asset, wallet).await?;
 
         let (px, sz_decimals) = self
             .calculate_slippage_price(params.asset, szi < 0.0, slippage, params.px)
@@ -387,79 +686,238 @@ impl<T: Signer> ExchangeClient<T> {
 
         let order = ClientOrderRequest {
             asset: params.asset.to_string(),
-            is_buy: szi < 0.0,
+            side: (szi < 0.0).into(),
             reduce_only: true,
             limit_px: px,
             sz,
             cloid: params.cloid,
-            order_type: ClientOrder::Limit(ClientLimit {
-                tif: "Ioc".to_string(),
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Ioc }),
+        };
+
+        self.order(order, Some(wallet), grouping).await
+    }
+
+    // Places a reduce-only market trigger order that closes the position once `trigger_px` is reached
+    pub async fn stop_loss(
+        &self,
+        params: PositionTpslParams<'_, T>,
+        grouping: Grouping,
+    ) -> Result<ExchangeResponseStatus> {
+        self.position_tpsl(params, "sl", grouping).await
+    }
+
+    pub async fn take_profit(
+        &self,
+        params: PositionTpslParams<'_, T>,
+        grouping: Grouping,
+    ) -> Result<ExchangeResponseStatus> {
+        self.position_tpsl(params, "tp", grouping).await
+    }
+
+    async fn position_tpsl(
+        &self,
+        params: PositionTpslParams<'_, T>,
+        tpsl: &str,
+        grouping: Grouping,
+    ) -> Result<ExchangeResponseStatus> {
+        let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
+        let wallet = params.wallet.unwrap_or(&self.wallet);
+
+        let szi = self.position_szi(params.asset, wallet).await?;
+        let side = Side::from(szi < 0.0);
+
+        // The limit price bounds the slippage of the market order sent when the trigger fires
+        let (limit_px, sz_decimals) = self
+            .calculate_slippage_price(params.asset, side, slippage, Some(params.trigger_px))
+            .await?;
+        let trigger_px = round_to_significant_and_decimal(
+            params.trigger_px,
+            MAX_SIG_FIGS,
+            PERP_MAX_DECIMALS.saturating_sub(sz_decimals),
+        );
+
+        let order = ClientOrderRequest {
+            asset: params.asset.to_string(),
+            side,
+            reduce_only: true,
+            limit_px,
+            sz: round_to_decimals(params.sz.unwrap_or_else(|| szi.abs()), sz_decimals),
+            cloid: params.cloid,
+            order_type: ClientOrder::Trigger(ClientTrigger {
+                is_market: true,
+                trigger_px,
+                tpsl: tpsl.to_string(),
             }),
         };
 
         self.order(order, Some(wallet), grouping).await
     }
 
-    async fn calculate_slippage_price(
+    async fn position_szi(&self, asset: &str, wallet: &T) -> Result<f64> {
+        let user_state = self.info_client.user_state(wallet.address()).await?;
+
+        let position = user_state
+            .asset_positions
+            .iter()
+            .find(|p| p.position.coin == asset)
+            .ok_or(Error::AssetNotFound)?;
+
+        position
+            .position
+            .szi
+            .parse::<f64>()
+            .map_err(|_| Error::FloatStringParse)
+    }
+
+    // Largest size the withdrawable margin supports at `leverage`, an opposite position is closed by
+    // the order first and adds its size on top
+    pub async fn max_order_size(
         &self,
         asset: &str,
-        is_buy: bool,
-        slippage: f64,
-        px: Option<f64>,
-    ) -> Result<(f64, u32)> {
-        let base_url = match self.http_client.base_url.as_str() {
-            "https://api.hyperliquid.xyz" => BaseUrl::Mainnet,
-            "https://api.hyperliquid-testnet.xyz" => BaseUrl::Testnet,
-            _ => return Err(Error::GenericRequest("Invalid base URL".to_string())),
-        };
-        let info_client = InfoClient::new(None, Some(base_url)).await?;
-        let meta = info_client.meta().await?;
+        leverage: u32,
+        side: impl Into<Side>,
+    ) -> Result<f64> {
+        let (sz_decimals, _) = self.asset_decimals(asset)?;
+        let user_state = self.info_client.user_state(self.user(None)).await?;
+        let (meta, asset_ctxs) = self.info_client.meta_and_asset_ctxs().await?;
 
-        let asset_meta = meta
+        let index = meta
             .universe
             .iter()
-            .find(|a| a.name == asset)
+            .position(|a| a.name == asset)
             .ok_or(Error::AssetNotFound)?;
+        let mark_px = asset_ctxs
+            .get(index)
+            .ok_or(Error::AssetNotFound)?
+            .shared
+            .mark_px
+            .parse::<f64>()
+            .map_err(|_| Error::FloatStringParse)?;
+        let withdrawable = user_state
+            .withdrawable
+            .parse::<f64>()
+            .map_err(|_| Error::FloatStringParse)?;
+        let szi = match user_state
+            .asset_positions
+            .iter()
+            .find(|p| p.position.coin == asset)
+        {
+            Some(position) => position
+                .position
+                .szi
+                .parse::<f64>()
+                .map_err(|_| Error::FloatStringParse)?,
+            None => 0.,
+        };
+
+        Ok(Self::max_size(
+            withdrawable,
+            leverage,
+            mark_px,
+            szi,
+            side.into(),
+            sz_decimals,
+        ))
+    }
+
+    fn max_size(
+        withdrawable: f64,
+        leverage: u32,
+        mark_px: f64,
+        szi: f64,
+        side: Side,
+        sz_decimals: u32,
+    ) -> f64 {
+        if mark_px <= 0. {
+            return 0.;
+        }
+        let opposite_sz = match side {
+            Side::Buy if szi < 0. => -szi,
+            Side::Sell if szi > 0. => szi,
+            _ => 0.,
+        };
+        let sz = withdrawable.max(0.) * leverage as f64 / mark_px + opposite_sz;
+        // Round down so the order never needs more margin than is available
+        truncate_float(sz, sz_decimals, false)
+    }
 
-        let sz_decimals = asset_meta.sz_decimals;
-        let max_decimals: u32 = if self.coin_to_asset[asset] < 10000 {
-            6
+    // Size and price decimals allowed for the perp or spot asset by the cached metas
+    fn asset_decimals(&self, asset: &str) -> Result<(u32, u32)> {
+        let &asset_index = self.coin_to_asset.get(asset).ok_or(Error::AssetNotFound)?;
+        let (sz_decimals, max_decimals) = if asset_index < 10000 {
+            let sz_decimals = self.sz_decimals(asset).ok_or(Error::AssetNotFound)?;
+            (sz_decimals, PERP_MAX_DECIMALS)
         } else {
-            8
+            let (_, sz_decimals) = self
+                .spot_pairs
+                .get(&asset_index)
+                .ok_or(Error::AssetNotFound)?;
+            (*sz_decimals, SPOT_MAX_DECIMALS)
         };
-        let price_decimals = max_decimals.saturating_sub(sz_decimals);
+        Ok((sz_decimals, max_decimals.saturating_sub(sz_decimals)))
+    }
+
+    fn validate_order(&self, order: &ClientOrderRequest) -> Result<()> {
+        let (sz_decimals, price_decimals) = self.asset_decimals(&order.asset)?;
+        order.validate_decimals(sz_decimals, price_decimals)
+    }
+
+    pub fn round_price(&self, asset: &str, px: f64) -> Result<f64> {
+        let (_, price_decimals) = self.asset_decimals(asset)?;
+        Ok(round_to_significant_and_decimal(
+            px,
+            MAX_SIG_FIGS,
+            price_decimals,
+        ))
+    }
+
+    pub fn round_size(&self, asset: &str, sz: f64) -> Result<f64> {
+        let (sz_decimals, _) = self.asset_decimals(asset)?;
+        Ok(round_to_decimals(sz, sz_decimals))
+    }
+
+    async fn calculate_slippage_price(
+        &self,
+        asset: &str,
+        side: impl Into<Side>,
+        slippage: f64,
+        px: Option<f64>,
+    ) -> Result<(f64, u32)> {
+        let (sz_decimals, price_decimals) = self.asset_decimals(asset)?;
 
         let px = if let Some(px) = px {
             px
         } else {
-            let all_mids = info_client.all_mids().await?;
-            all_mids
-                .get(asset)
-                .ok_or(Error::AssetNotFound)?
-                .parse::<f64>()
-                .map_err(|_| Error::FloatStringParse)?
+            let all_mids = self.info_client.all_mids_for_dex(None).await?;
+            all_mids.get_f64(asset).ok_or(Error::AssetNotFound)?
         };
 
         debug!("px before slippage: {px:?}");
+        let px = Self::slippage_px(px, side.into().is_buy(), slippage, price_decimals);
+        debug!("px after slippage: {px:?}");
+        Ok((px, sz_decimals))
+    }
+
+    fn slippage_px(px: f64, is_buy: bool, slippage: f64, price_decimals: u32) -> f64 {
         let slippage_factor = if is_buy {
             1.0 + slippage
         } else {
             1.0 - slippage
         };
-        let px = px * slippage_factor;
-
-        // Round to the correct number of decimal places and significant figures
-        let px = round_to_significant_and_decimal(px, 5, price_decimals);
-
-        debug!("px after slippage: {px:?}");
-        Ok((px, sz_decimals))
+        // Round away from the mid so the order crosses by at least the requested slippage
+        round_to_significant_and_decimal_directed(
+            px * slippage_factor,
+            MAX_SIG_FIGS,
+            price_decimals,
+            is_buy,
+        )
     }
 
     pub async fn order(
         &self,
         order: ClientOrderRequest,
         wallet: Option<&T>,
-        grouping: String,
+        grouping: Grouping,
     ) -> Result<ExchangeResponseStatus> {
         self.bulk_order(vec![order], wallet, grouping).await
     }
@@ -469,37 +927,130 @@ impl<T: Signer> ExchangeClient<T> {
         order: ClientOrderRequest,
         wallet: Option<&T>,
         builder: BuilderInfo,
-        grouping: String,
+        grouping: Grouping,
     ) -> Result<ExchangeResponseStatus> {
-        self.bulk_order_with_builder(vec![order], wallet, builder, grouping).await
+        self.bulk_order_with_builder(vec![order], wallet, builder, grouping)
+            .await
+    }
+
+    // Where an order stands right after placing it, `Resting` orders are followed further
+    fn placed_order_status(status: Option<&ExchangeDataStatus>) -> Result<OrderWaitStatus> {
+        let accepted = |status: &str| OrderWaitStatus::Accepted {
+            status: status.to_string(),
+        };
+        Ok(match status {
+            Some(ExchangeDataStatus::Resting(resting_order)) => OrderWaitStatus::Resting {
+                oid: resting_order.oid,
+            },
+            Some(ExchangeDataStatus::Filled(filled_order)) => OrderWaitStatus::Filled {
+                oid: filled_order.oid,
+            },
+            Some(ExchangeDataStatus::Error(e)) => OrderWaitStatus::Rejected(e.clone()),
+            Some(ExchangeDataStatus::Success) => accepted("success"),
+            Some(ExchangeDataStatus::WaitingForFill) => accepted("waitingForFill"),
+            Some(ExchangeDataStatus::WaitingForTrigger) => accepted("waitingForTrigger"),
+            None => {
+                return Err(Error::GenericParse(
+                    "Order response without a status".to_string(),
+                ))
+            }
+        })
+    }
+
+    // `None` while the order is still on the book
+    fn order_update_status(oid: u64, status: &str) -> Option<OrderWaitStatus> {
+        match status {
+            "open" | "triggered" => None,
+            "filled" => Some(OrderWaitStatus::Filled { oid }),
+            status => Some(OrderWaitStatus::Closed {
+                oid,
+                status: status.to_string(),
+            }),
+        }
+    }
+
+    // Places the order and follows its order updates until it is filled or closed. Updates sent
+    // before the exchange confirmed the subscription can be missed, so the order status is looked
+    // up once more when `timeout` expires
+    pub async fn order_and_wait(
+        &self,
+        order: ClientOrderRequest,
+        timeout: Duration,
+    ) -> Result<OrderWaitStatus> {
+        let user = self.user(None);
+        let (sender, receiver) = unbounded_channel();
+        let subscription_id = self
+            .info_client
+            .subscribe(Subscription::OrderUpdates { user }, sender)
+            .await?;
+
+        let status = self.wait_for_order(order, timeout, user, receiver).await;
+        self.info_client.unsubscribe(subscription_id).await?;
+        status
+    }
+
+    async fn wait_for_order(
+        &self,
+        order: ClientOrderRequest,
+        timeout: Duration,
+        user: Address,
+        mut receiver: UnboundedReceiver<Message>,
+    ) -> Result<OrderWaitStatus> {
+        let response = self.order(order, None, Grouping::Na).await?;
+        if let ExchangeResponseStatus::Err(e) = &response {
+            return Ok(OrderWaitStatus::Rejected(e.clone()));
+        }
+        let oid = match Self::placed_order_status(response.statuses().next())? {
+            OrderWaitStatus::Resting { oid } => oid,
+            status => return Ok(status),
+        };
+
+        let resolution = async {
+            while let Some(message) = receiver.recv().await {
+                let Message::OrderUpdates(order_updates) = message else {
+                    continue;
+                };
+                for update in order_updates.data.iter().filter(|u| u.order.oid == oid) {
+                    if let Some(status) = Self::order_update_status(oid, &update.status) {
+                        return Some(status);
+                    }
+                }
+            }
+            None
+        };
+        if let Ok(Some(status)) = time::timeout(timeout, resolution).await {
+            return Ok(status);
+        }
+        let order_status = self.info_client.order_status(user, oid).await?;
+        Ok(order_status
+            .order
+            .and_then(|order| Self::order_update_status(oid, &order.status))
+            .unwrap_or(OrderWaitStatus::Resting { oid }))
     }
 
     pub async fn bulk_order(
         &self,
         orders: Vec<ClientOrderRequest>,
         wallet: Option<&T>,
-        grouping: String,
+        grouping: Grouping,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
+        if orders.is_empty() {
+            return Err(Error::EmptyBatch);
+        }
 
         let mut transformed_orders = Vec::new();
 
         for order in orders {
+            self.validate_order(&order)?;
             transformed_orders.push(order.convert(&self.coin_to_asset)?);
         }
 
         let action = Actions::Order(BulkOrder {
             orders: transformed_orders,
-            grouping: grouping,
+            grouping,
             builder: None,
         });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet).await?;
-        self.post(action, signature, timestamp).await
+        self.post_order_action(action, wallet).await
     }
 
     pub async fn bulk_order_with_builder(
@@ -507,30 +1058,29 @@ impl<T: Signer> ExchangeClient<T> {
         orders: Vec<ClientOrderRequest>,
         wallet: Option<&T>,
         mut builder: BuilderInfo,
-        grouping: String,
+        grouping: Grouping,
     ) -> Result<ExchangeResponseStatus> {
+        if orders.is_empty() {
+            return Err(Error::EmptyBatch);
+        }
         let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
 
         builder.builder = builder.builder.to_lowercase();
+        self.check_builder_fee(&builder).await?;
 
         let mut transformed_orders = Vec::new();
 
         for order in orders {
+            self.validate_order(&order)?;
             transformed_orders.push(order.convert(&self.coin_to_asset)?);
         }
 
         let action = Actions::Order(BulkOrder {
             orders: transformed_orders,
-            grouping: grouping,
+            grouping,
             builder: Some(builder),
         });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet).await?;
-        self.post(action, signature, timestamp).await
+        self.post_order_action(action, Some(wallet)).await
     }
 
     pub async fn cancel(
@@ -546,8 +1096,9 @@ impl<T: Signer> ExchangeClient<T> {
         cancels: Vec<ClientCancelRequest>,
         wallet: Option<&T>,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
+        if cancels.is_empty() {
+            return Err(Error::EmptyBatch);
+        }
 
         let mut transformed_cancels = Vec::new();
         for cancel in cancels.into_iter() {
@@ -564,13 +1115,89 @@ impl<T: Signer> ExchangeClient<T> {
         let action = Actions::Cancel(BulkCancel {
             cancels: transformed_cancels,
         });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
+        self.post_order_action(action, wallet).await
+    }
 
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet).await?;
+    pub async fn cancel_all(&self, wallet: Option<&T>) -> Result<ExchangeResponseStatus> {
+        self.cancel_open_orders(None, wallet).await
+    }
 
-        self.post(action, signature, timestamp).await
+    pub async fn cancel_all_for_asset(
+        &self,
+        asset: &str,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.cancel_open_orders(Some(asset), wallet).await
+    }
+
+    // Orders with a builder fee above the user's approved max fee are rejected by the exchange
+    // An approval only grows until it is revoked, so a cached fee is trusted when it covers the
+    // order and fetched again otherwise
+    async fn check_builder_fee(&self, builder: &BuilderInfo) -> Result<()> {
+        let builder_address = builder.builder.parse().map_err(|_| {
+            Error::GenericParse(format!("Invalid builder address {}", builder.builder))
+        })?;
+        let cached = self.cached_builder_fee(builder_address);
+        if cached.is_some_and(|approved| builder.fee <= approved) {
+            return Ok(());
+        }
+        let approved = self
+            .info_client
+            .max_builder_fee(self.user(None), builder_address)
+            .await?;
+        if let Ok(mut approved_builder_fees) = self.approved_builder_fees.lock() {
+            approved_builder_fees.insert(builder_address, approved);
+        }
+        if builder.fee > approved {
+            return Err(Error::BuilderFeeNotApproved {
+                required: builder.fee,
+                approved,
+            });
+        }
+        Ok(())
+    }
+
+    // The account whose orders and positions the actions of `wallet` act on: the vault, the master
+    // account of an agent, or else the signer itself
+    fn user(&self, wallet: Option<&T>) -> Address {
+        self.vault_address
+            .or(self.account_address)
+            .unwrap_or_else(|| wallet.unwrap_or(&self.wallet).address())
+    }
+
+    fn cached_builder_fee(&self, builder: Address) -> Option<u64> {
+        self.approved_builder_fees
+            .lock()
+            .ok()?
+            .get(&builder)
+            .copied()
+    }
+
+    async fn cancel_open_orders(
+        &self,
+        asset: Option<&str>,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let open_orders = self.info_client.open_orders(self.user(wallet)).await?;
+
+        let cancels: Vec<ClientCancelRequest> = open_orders
+            .into_iter()
+            .filter(|order| asset.is_none_or(|asset| order.coin == asset))
+            .map(|order| ClientCancelRequest {
+                asset: order.coin,
+                oid: order.oid,
+            })
+            .collect();
+
+        // The exchange rejects an empty cancel, so report nothing was cancelled instead
+        if cancels.is_empty() {
+            return Ok(ExchangeResponseStatus::Ok(ExchangeResponse {
+                response_type: "cancel".to_string(),
+                data: Some(ExchangeDataStatuses { statuses: vec![] }),
+                raw_data: None,
+            }));
+        }
+        self.bulk_cancel(cancels, wallet).await
     }
 
     pub async fn modify(
@@ -581,13 +1208,29 @@ impl<T: Signer> ExchangeClient<T> {
         self.bulk_modify(vec![modify], wallet).await
     }
 
+    // A modify is one atomic action that keeps the oid, while cancelling and placing a new order
+    // takes two nonces and leaves nothing on the book in between. In both cases the new order
+    // queues behind the orders already resting at its price.
+    pub async fn replace_order(
+        &self,
+        order: &FrontendOpenOrdersResponse,
+        limit_px: f64,
+        sz: f64,
+        cloid: Option<Uuid>,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let modify = ClientModifyRequest::replace(order, limit_px, sz, cloid)?;
+        self.modify(modify, wallet).await
+    }
+
     pub async fn bulk_modify(
         &self,
         modifies: Vec<ClientModifyRequest>,
         wallet: Option<&T>,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
+        if modifies.is_empty() {
+            return Err(Error::EmptyBatch);
+        }
 
         let mut transformed_modifies = Vec::new();
         for modify in modifies.into_iter() {
@@ -600,13 +1243,7 @@ impl<T: Signer> ExchangeClient<T> {
         let action = Actions::BatchModify(BulkModify {
             modifies: transformed_modifies,
         });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet).await?;
-
-        self.post(action, signature, timestamp).await
+        self.post_order_action(action, wallet).await
     }
 
     pub async fn cancel_by_cloid(
@@ -622,8 +1259,9 @@ impl<T: Signer> ExchangeClient<T> {
         cancels: Vec<ClientCancelRequestCloid>,
         wallet: Option<&T>,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
+        if cancels.is_empty() {
+            return Err(Error::EmptyBatch);
+        }
 
         let mut transformed_cancels: Vec<CancelRequestCloid> = Vec::new();
         for cancel in cancels.into_iter() {
@@ -640,13 +1278,7 @@ impl<T: Signer> ExchangeClient<T> {
         let action = Actions::CancelByCloid(BulkCancelCloid {
             cancels: transformed_cancels,
         });
-
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet).await?;
-
-        self.post(action, signature, timestamp).await
+        self.post_order_action(action, wallet).await
     }
 
     pub async fn update_leverage(
@@ -676,13 +1308,13 @@ impl<T: Signer> ExchangeClient<T> {
 
     pub async fn update_isolated_margin(
         &self,
-        amount: f64,
+        amount: impl Into<Amount>,
         coin: &str,
         wallet: Option<&T>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
 
-        let amount = (amount * 1_000_000.0).round() as i64;
+        let amount = amount.into().to_micro_usdc()?;
         let timestamp = next_nonce();
 
         let &asset_index = self.coin_to_asset.get(coin).ok_or(Error::AssetNotFound)?;
@@ -703,16 +1335,48 @@ impl<T: Signer> ExchangeClient<T> {
         &self,
         wallet: Option<&T>,
     ) -> Result<(String, ExchangeResponseStatus)> {
-        todo!("Approve agent not implemented")
+        self.approve_named_agent(None, wallet).await
+    }
+
+    pub async fn approve_named_agent(
+        &self,
+        agent_name: Option<String>,
+        wallet: Option<&T>,
+    ) -> Result<(String, ExchangeResponseStatus)> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let key = generate_random_key()?;
+        let agent = PrivateKeySigner::from_bytes(&B256::from(key))
+            .map_err(|e| Error::PrivateKeyParse(e.to_string()))?;
+
+        let hyperliquid_chain = if self.http_client.is_mainnet() {
+            "Mainnet".to_string()
+        } else {
+            "Testnet".to_string()
+        };
+
+        let nonce = next_nonce();
+        let approve_agent = ApproveAgent {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain,
+            agent_address: agent.address(),
+            agent_name,
+            nonce,
+        };
+        let signature = sign_typed_data(&approve_agent, wallet).await?;
+        let action = serde_json::to_value(Actions::ApproveAgent(approve_agent))
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        Ok((hex::encode(key), self.post(action, signature, nonce).await?))
     }
 
     pub async fn withdraw_from_bridge(
         &self,
-        amount: &str,
+        amount: impl Into<Amount>,
         destination: &str,
         wallet: Option<&T>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
+        let amount = amount.into();
+        Self::check_withdraw_amount(&amount)?;
         let hyperliquid_chain = if self.http_client.is_mainnet() {
             "Mainnet".to_string()
         } else {
@@ -721,7 +1385,7 @@ impl<T: Signer> ExchangeClient<T> {
 
         let timestamp = next_nonce();
         let withdraw = Withdraw3 {
-            signature_chain_id: U256::from(421614),
+            signature_chain_id: self.signature_chain_id(),
             hyperliquid_chain,
             destination: destination.to_string(),
             amount: amount.to_string(),
@@ -734,9 +1398,109 @@ impl<T: Signer> ExchangeClient<T> {
         self.post(action, signature, timestamp).await
     }
 
+    // The fee is taken from the withdrawn amount, anything not above it would be lost entirely
+    fn check_withdraw_amount(amount: &Amount) -> Result<()> {
+        let amount = amount
+            .to_string()
+            .parse::<f64>()
+            .map_err(|_| Error::FloatStringParse)?;
+        if amount <= WITHDRAW_FEE_USD {
+            return Err(Error::WithdrawBelowMinimum {
+                amount,
+                fee: WITHDRAW_FEE_USD,
+            });
+        }
+        Ok(())
+    }
+
+    // `wei` is in the token's smallest unit, HYPE has 8 wei decimals
+    pub async fn token_delegate(
+        &self,
+        validator: Address,
+        wei: u64,
+        is_undelegate: bool,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let hyperliquid_chain = if self.http_client.is_mainnet() {
+            "Mainnet".to_string()
+        } else {
+            "Testnet".to_string()
+        };
+
+        let timestamp = next_nonce();
+        let token_delegate = TokenDelegate {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain,
+            validator,
+            wei,
+            is_undelegate,
+            nonce: timestamp,
+        };
+        let signature = sign_typed_data(&token_delegate, wallet).await?;
+        let action = serde_json::to_value(Actions::TokenDelegate(token_delegate))
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        self.post(action, signature, timestamp).await
+    }
+
+    // Moves tokens from the spot balance to the staking balance, where they can be delegated
+    pub async fn stake_deposit(
+        &self,
+        wei: u64,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let hyperliquid_chain = if self.http_client.is_mainnet() {
+            "Mainnet".to_string()
+        } else {
+            "Testnet".to_string()
+        };
+
+        let timestamp = next_nonce();
+        let action = CDeposit {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain,
+            wei,
+            nonce: timestamp,
+        };
+        let signature = sign_typed_data(&action, wallet).await?;
+        let action = serde_json::to_value(Actions::CDeposit(action))
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        self.post(action, signature, timestamp).await
+    }
+
+    // Undelegated staking balance returns to spot after the 7 day unstaking queue
+    pub async fn stake_withdraw(
+        &self,
+        wei: u64,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let hyperliquid_chain = if self.http_client.is_mainnet() {
+            "Mainnet".to_string()
+        } else {
+            "Testnet".to_string()
+        };
+
+        let timestamp = next_nonce();
+        let action = CWithdraw {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain,
+            wei,
+            nonce: timestamp,
+        };
+        let signature = sign_typed_data(&action, wallet).await?;
+        let action = serde_json::to_value(Actions::CWithdraw(action))
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        self.post(action, signature, timestamp).await
+    }
+
     pub async fn spot_transfer(
         &self,
-        amount: &str,
+        amount: impl Into<Amount>,
         destination: &str,
         token: &str,
         wallet: Option<&T>,
@@ -750,10 +1514,10 @@ impl<T: Signer> ExchangeClient<T> {
 
         let timestamp = next_nonce();
         let spot_send = SpotSend {
-            signature_chain_id: U256::from(421614),
+            signature_chain_id: self.signature_chain_id(),
             hyperliquid_chain,
             destination: destination.to_string(),
-            amount: amount.to_string(),
+            amount: amount.into().to_string(),
             time: timestamp,
             token: token.to_string(),
         };
@@ -764,6 +1528,47 @@ impl<T: Signer> ExchangeClient<T> {
         self.post(action, signature, timestamp).await
     }
 
+    // Moves `token` between dexes, "" being the default perp dex and "spot" the spot balance.
+    // `token` is "<name>:<token id>" as in `spot_transfer`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_asset(
+        &self,
+        destination: &str,
+        source_dex: &str,
+        destination_dex: &str,
+        token: &str,
+        amount: impl Into<Amount>,
+        from_sub_account: Option<Address>,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let hyperliquid_chain = if self.http_client.is_mainnet() {
+            "Mainnet".to_string()
+        } else {
+            "Testnet".to_string()
+        };
+
+        let timestamp = next_nonce();
+        let send_asset = SendAsset {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain,
+            destination: destination.to_string(),
+            source_dex: source_dex.to_string(),
+            destination_dex: destination_dex.to_string(),
+            token: token.to_string(),
+            amount: amount.into().to_string(),
+            from_sub_account: from_sub_account
+                .map(|address| address.to_string().to_lowercase())
+                .unwrap_or_default(),
+            nonce: timestamp,
+        };
+        let signature = sign_typed_data(&send_asset, wallet).await?;
+        let action = serde_json::to_value(Actions::SendAsset(send_asset))
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        self.post(action, signature, timestamp).await
+    }
+
     pub async fn set_referrer(
         &self,
         code: String,
@@ -781,17 +1586,674 @@ impl<T: Signer> ExchangeClient<T> {
         let signature = sign_l1_action(wallet, connection_id, is_mainnet).await?;
         self.post(action, signature, timestamp).await
     }
-}
 
-fn round_to_decimals(value: f64, decimals: u32) -> f64 {
-    let factor = 10f64.powi(decimals as i32);
-    (value * factor).round() / factor
+    // Turns the wallet's account into a multi-sig account, actions then need `threshold` of the
+    // authorized users' signatures. An empty list of users converts it back to a normal account.
+    pub async fn convert_to_multi_sig_user(
+        &self,
+        mut authorized_users: Vec<Address>,
+        threshold: u64,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let hyperliquid_chain = if self.http_client.is_mainnet() {
+            "Mainnet".to_string()
+        } else {
+            "Testnet".to_string()
+        };
+
+        authorized_users.sort();
+        let authorized_users: Vec<String> = authorized_users
+            .iter()
+            .map(|user| user.to_string().to_lowercase())
+            .collect();
+        let signers = serde_json::to_string(&serde_json::json!({
+            "authorizedUsers": authorized_users,
+            "threshold": threshold,
+        }))
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        let timestamp = next_nonce();
+        let action = ConvertToMultiSigUser {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain,
+            signers,
+            nonce: timestamp,
+        };
+        let signature = sign_typed_data(&action, wallet).await?;
+        let action = serde_json::to_value(Actions::ConvertToMultiSigUser(action))
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        self.post(action, signature, timestamp).await
+    }
+
+    // Signature of one of the authorized users of `multi_sig_user` over an L1 action, to be
+    // submitted by `outer_signer` through `multi_sig` with the same nonce
+    pub async fn sign_multi_sig_action(
+        &self,
+        multi_sig_user: Address,
+        outer_signer: Address,
+        action: &Actions,
+        nonce: u64,
+        wallet: Option<&T>,
+    ) -> Result<Signature> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let envelope = (
+            multi_sig_user.to_string().to_lowercase(),
+            outer_signer.to_string().to_lowercase(),
+            action,
+        );
+        let connection_id = hash_action(&envelope, nonce, self.vault_address)?;
+        let is_mainnet = self.http_client.is_mainnet();
+        sign_l1_action(wallet, connection_id, is_mainnet).await
+    }
+
+    pub async fn multi_sig(
+        &self,
+        multi_sig_user: Address,
+        action: Actions,
+        signatures: Vec<Signature>,
+        nonce: u64,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let hyperliquid_chain = if self.http_client.is_mainnet() {
+            "Mainnet".to_string()
+        } else {
+            "Testnet".to_string()
+        };
+
+        let multi_sig = MultiSig {
+            signature_chain_id: format!("{:#x}", self.signature_chain_id()),
+            signatures: signatures.iter().map(MultiSigSignature::from).collect(),
+            payload: MultiSigPayload {
+                multi_sig_user: multi_sig_user.to_string().to_lowercase(),
+                outer_signer: wallet.address().to_string().to_lowercase(),
+                action: Box::new(action),
+            },
+        };
+        // The envelope is hashed without its type tag
+        let multi_sig_action_hash = hash_action(&multi_sig, nonce, self.vault_address)?;
+        let send_multi_sig = SendMultiSig {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain,
+            multi_sig_action_hash,
+            nonce,
+        };
+        let signature = sign_typed_data(&send_multi_sig, wallet).await?;
+
+        let action = serde_json::to_value(Actions::MultiSig(multi_sig))
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        self.post(action, signature, nonce).await
+    }
+
+    // Signed and posted like any other L1 action but has no effect on the account
+    pub async fn noop(&self, wallet: Option<&T>) -> Result<ExchangeResponseStatus> {
+        self.invalidate_nonce(next_nonce(), wallet).await
+    }
+
+    // Uses up `nonce` so an action signed earlier with the same nonce can no longer be submitted
+    pub async fn invalidate_nonce(
+        &self,
+        nonce: u64,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = Actions::Noop;
+        self.post_l1_action_with_nonce(&action, nonce, wallet).await
+    }
+
+    // `time` (ms) must be at least 5 seconds in the future; `None` removes the scheduled cancel
+    pub async fn schedule_cancel(
+        &self,
+        time: Option<u64>,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = Actions::ScheduleCancel(ScheduleCancel { time });
+        self.post_l1_action(&action, wallet).await
+    }
+
+    // Registers a new asset on the builder deployed perp dex `dex`. `max_gas` caps the deploy
+    // auction price, None takes the current price
+    pub async fn perp_deploy_register_asset(
+        &self,
+        dex: &str,
+        max_gas: Option<u64>,
+        asset_request: PerpAssetRequest,
+        schema: Option<PerpDexSchema>,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let schema = schema.map(|schema| PerpDexSchema {
+            oracle_updater: schema.oracle_updater.map(|updater| updater.to_lowercase()),
+            ..schema
+        });
+        let action = Actions::PerpDeploy(PerpDeploy::RegisterAsset(PerpDeployRegisterAsset {
+            max_gas,
+            asset_request,
+            dex: dex.to_string(),
+            schema,
+        }));
+        self.post_l1_action(&action, wallet).await
+    }
+
+    // `mark_pxs` holds one map per mark price source, the exchange takes their median
+    pub async fn perp_deploy_set_oracle(
+        &self,
+        dex: &str,
+        oracle_pxs: BTreeMap<String, String>,
+        mark_pxs: Vec<BTreeMap<String, String>>,
+        external_perp_pxs: BTreeMap<String, String>,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = Actions::PerpDeploy(PerpDeploy::SetOracle(PerpDeploySetOracle {
+            dex: dex.to_string(),
+            oracle_pxs: oracle_pxs.into_iter().collect(),
+            mark_pxs: mark_pxs
+                .into_iter()
+                .map(|mark_pxs| mark_pxs.into_iter().collect())
+                .collect(),
+            external_perp_pxs: external_perp_pxs.into_iter().collect(),
+        }));
+        self.post_l1_action(&action, wallet).await
+    }
+
+    // Opens the deploy auction for a new token; `max_gas` caps the auction price
+    pub async fn spot_deploy_register_token(
+        &self,
+        spec: SpotTokenSpec,
+        max_gas: u64,
+        full_name: String,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = Actions::SpotDeploy(SpotDeploy::RegisterToken2(SpotDeployRegisterToken {
+            spec,
+            max_gas,
+            full_name,
+        }));
+        self.post_l1_action(&action, wallet).await
+    }
+
+    // Balances credited at genesis, `user_and_wei` in the new token's wei and
+    // `existing_token_and_wei` to holders of existing tokens pro rata
+    pub async fn spot_deploy_user_genesis(
+        &self,
+        token: u32,
+        user_and_wei: Vec<(Address, String)>,
+        existing_token_and_wei: Vec<(u32, String)>,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = Actions::SpotDeploy(SpotDeploy::UserGenesis(SpotDeployUserGenesis {
+            token,
+            user_and_wei: user_and_wei
+                .into_iter()
+                .map(|(user, wei)| (user.to_string().to_lowercase(), wei))
+                .collect(),
+            existing_token_and_wei,
+        }));
+        self.post_l1_action(&action, wallet).await
+    }
+
+    pub async fn spot_deploy_genesis(
+        &self,
+        token: u32,
+        max_supply: String,
+        no_hyperliquidity: bool,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = Actions::SpotDeploy(SpotDeploy::Genesis(SpotDeployGenesis {
+            token,
+            max_supply,
+            no_hyperliquidity: no_hyperliquidity.then_some(true),
+        }));
+        self.post_l1_action(&action, wallet).await
+    }
+
+    pub async fn spot_deploy_register_spot(
+        &self,
+        base_token: u32,
+        quote_token: u32,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = Actions::SpotDeploy(SpotDeploy::RegisterSpot(SpotDeployRegisterSpot {
+            tokens: [base_token, quote_token],
+        }));
+        self.post_l1_action(&action, wallet).await
+    }
+
+    // `share` is a percentage string such as "100%"
+    pub async fn spot_deploy_set_deployer_trading_fee_share(
+        &self,
+        token: u32,
+        share: String,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = Actions::SpotDeploy(SpotDeploy::SetDeployerTradingFeeShare(
+            SpotDeploySetDeployerTradingFeeShare { token, share },
+        ));
+        self.post_l1_action(&action, wallet).await
+    }
+
+    async fn post_l1_action(
+        &self,
+        action: &Actions,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.post_l1_action_with_nonce(action, next_nonce(), wallet)
+            .await
+    }
+
+    async fn post_l1_action_with_nonce(
+        &self,
+        action: &Actions,
+        timestamp: u64,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+
+        let connection_id = action.hash(timestamp, self.vault_address)?;
+        let action = serde_json::to_value(action).map_err(|e| Error::JsonParse(e.to_string()))?;
+        let is_mainnet = self.http_client.is_mainnet();
+        let signature = sign_l1_action(wallet, connection_id, is_mainnet).await?;
+        self.post(action, signature, timestamp).await
+    }
+
+    // With `with_auto_retry`, an order, cancel or modify action is signed again with a fresh nonce
+    // and resent once if the exchange rejected its nonce or rate limited the whole request, see
+    // `retry_delay`
+    async fn post_order_action(
+        &self,
+        action: Actions,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let response = self.post_l1_action(&action, wallet).await;
+        let delay = match retry_delay(&response) {
+            Some(delay) if self.auto_retry => delay,
+            _ => return response,
+        };
+        warn!("Resending action with a fresh nonce in {delay:?} after {response:?}");
+        time::sleep(delay).await;
+        self.post_l1_action(&action, wallet).await
+    }
+
+    // Paid for from the perp balance, the reserved weight is added to the address rate limit
+    pub async fn reserve_request_weight(
+        &self,
+        weight: u64,
+        wallet: Option<&T>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = Actions::ReserveRequestWeight(ReserveRequestWeight { weight });
+        self.post_l1_action(&action, wallet).await
+    }
 }
 
-fn round_to_significant_and_decimal(value: f64, sig_figs: u32, max_decimals: u32) -> f64 {
-    let abs_value = value.abs();
-    let magnitude = abs_value.log10().floor() as i32;
-    let scale = 10f64.powi(sig_figs as i32 - magnitude - 1);
-    let rounded = (abs_value * scale).round() / scale;
-    round_to_decimals(rounded.copysign(value), max_decimals)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActionBatch, RestingOrder};
+
+    // A client for the given meta that never reaches the exchange
+    fn test_client(meta: Meta) -> ExchangeClient<PrivateKeySigner> {
+        let coin_to_asset = meta
+            .universe
+            .iter()
+            .enumerate()
+            .map(|(asset, asset_meta)| (asset_meta.name.clone(), asset as u32))
+            .collect();
+        let http_client = HttpClient::new(Client::new(), "https://api.hyperliquid.xyz".to_string());
+        ExchangeClient {
+            info_client: InfoClient::from_http_client(http_client.clone()),
+            http_client,
+            wallet: PrivateKeySigner::random(),
+            coin_to_sz_decimals: ExchangeClient::<PrivateKeySigner>::coin_to_sz_decimals(&meta),
+            spot_pairs: HashMap::new(),
+            meta,
+            vault_address: None,
+            coin_to_asset,
+            auto_retry: false,
+            account_address: None,
+            approved_builder_fees: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[test]
+    fn action_hash_commits_to_nonce_and_vault() -> Result<()> {
+        let action = Actions::ScheduleCancel(ScheduleCancel { time: None });
+        let mut exchange_client = test_client(Meta { universe: vec![] });
+        let hash = exchange_client.action_hash(&action, 1700000000000)?;
+        assert_eq!(hash, action.hash(1700000000000, None)?);
+        assert_ne!(hash, exchange_client.action_hash(&action, 1700000000001)?);
+
+        exchange_client.vault_address = Some(Address::repeat_byte(0x11));
+        assert_ne!(hash, exchange_client.action_hash(&action, 1700000000000)?);
+        Ok(())
+    }
+
+    #[test]
+    fn perp_deploy_actions_nest_under_their_kind() {
+        let action = Actions::PerpDeploy(PerpDeploy::SetOracle(PerpDeploySetOracle {
+            dex: "test".to_string(),
+            oracle_pxs: vec![("test:ABC".to_string(), "10.5".to_string())],
+            mark_pxs: vec![vec![("test:ABC".to_string(), "10.4".to_string())]],
+            external_perp_pxs: vec![],
+        }));
+        assert_eq!(
+            serde_json::to_value(&action).unwrap(),
+            serde_json::json!({
+                "type": "perpDeploy",
+                "setOracle": {
+                    "dex": "test",
+                    "oraclePxs": [["test:ABC", "10.5"]],
+                    "markPxs": [[["test:ABC", "10.4"]]],
+                    "externalPerpPxs": [],
+                },
+            })
+        );
+        assert!(action.hash(1700000000000, None).is_ok());
+    }
+
+    #[test]
+    fn spot_deploy_register_token_uses_the_v2_action() {
+        let action = Actions::SpotDeploy(SpotDeploy::RegisterToken2(SpotDeployRegisterToken {
+            spec: SpotTokenSpec {
+                name: "TEST".to_string(),
+                sz_decimals: 2,
+                wei_decimals: 8,
+            },
+            max_gas: 1_000_000,
+            full_name: "Test Token".to_string(),
+        }));
+        assert_eq!(
+            serde_json::to_value(&action).unwrap(),
+            serde_json::json!({
+                "type": "spotDeploy",
+                "registerToken2": {
+                    "spec": {"name": "TEST", "szDecimals": 2, "weiDecimals": 8},
+                    "maxGas": 1_000_000,
+                    "fullName": "Test Token",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn spot_slippage_prices_keep_up_to_eight_decimals() {
+        // PURR has no size decimals, leaving all 8 price decimals within 5 significant figures
+        let px = ExchangeClient::<PrivateKeySigner>::slippage_px(0.000123456, true, 0.05, 8);
+        assert_eq!(px, 0.00012963);
+        let px = ExchangeClient::<PrivateKeySigner>::slippage_px(0.000123456, false, 0.05, 8);
+        assert_eq!(px, 0.00011728);
+    }
+
+    #[test]
+    fn withdrawals_must_exceed_the_fee() {
+        type Client = ExchangeClient<PrivateKeySigner>;
+        assert!(Client::check_withdraw_amount(&Amount::from("1.5")).is_ok());
+        assert!(matches!(
+            Client::check_withdraw_amount(&Amount::from(1.0)),
+            Err(Error::WithdrawBelowMinimum { amount, .. }) if amount == 1.0
+        ));
+        assert!(matches!(
+            Client::check_withdraw_amount(&Amount::from("abc")),
+            Err(Error::FloatStringParse)
+        ));
+    }
+
+    #[test]
+    fn max_size_uses_leverage_and_closes_opposite_positions() {
+        type Client = ExchangeClient<PrivateKeySigner>;
+        assert_eq!(Client::max_size(1000., 5, 2000., 0., Side::Buy, 4), 2.5);
+        assert_eq!(Client::max_size(1000., 3, 2000., 0., Side::Sell, 2), 1.5);
+        assert_eq!(Client::max_size(1000., 2, 3000., 0., Side::Buy, 2), 0.66);
+        assert_eq!(Client::max_size(1000., 5, 2000., -1., Side::Buy, 4), 3.5);
+        assert_eq!(Client::max_size(1000., 5, 2000., 1., Side::Buy, 4), 2.5);
+    }
+
+    #[tokio::test]
+    async fn empty_batches_are_not_sent() {
+        let exchange_client = test_client(Meta { universe: vec![] });
+        assert!(matches!(
+            exchange_client.bulk_order(vec![], None, Grouping::Na).await,
+            Err(Error::EmptyBatch)
+        ));
+        assert!(matches!(
+            exchange_client.bulk_cancel(vec![], None).await,
+            Err(Error::EmptyBatch)
+        ));
+        assert!(matches!(
+            exchange_client.bulk_cancel_by_cloid(vec![], None).await,
+            Err(Error::EmptyBatch)
+        ));
+        assert!(matches!(
+            exchange_client.bulk_modify(vec![], None).await,
+            Err(Error::EmptyBatch)
+        ));
+    }
+
+    #[test]
+    fn sz_decimals_come_from_the_cached_meta() {
+        let meta: Meta = serde_json::from_str(
+            r#"{"universe":[{"name":"BTC","szDecimals":5,"maxLeverage":50},{"name":"ETH","szDecimals":4,"maxLeverage":50}]}"#,
+        )
+        .unwrap();
+        let exchange_client = test_client(meta);
+        assert_eq!(exchange_client.sz_decimals("ETH"), Some(4));
+        assert_eq!(exchange_client.sz_decimals("SOL"), None);
+        assert_eq!(exchange_client.asset_decimals("BTC").unwrap(), (5, 1));
+    }
+
+    #[test]
+    fn only_whole_request_rejections_are_retried() {
+        assert_eq!(
+            retry_delay(&Err(Error::NonceTooOld("nonce too old".to_string()))),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(
+            retry_delay(&Err(Error::ClientRequest {
+                status_code: 429,
+                error_code: None,
+                error_message: String::new(),
+                error_data: None,
+            })),
+            Some(RATE_LIMIT_BACKOFF)
+        );
+        assert_eq!(
+            retry_delay(&Ok(ExchangeResponseStatus::Err(
+                "Too many cumulative requests sent".to_string()
+            ))),
+            Some(RATE_LIMIT_BACKOFF)
+        );
+        assert!(retry_delay(&Ok(ExchangeResponseStatus::Err(
+            "Insufficient margin to place order.".to_string()
+        )))
+        .is_none());
+        assert!(retry_delay(&Err(Error::GenericRequest("connection reset".to_string()))).is_none());
+    }
+
+    #[tokio::test]
+    async fn builder_fees_covered_by_the_cached_approval_are_not_fetched() -> Result<()> {
+        let exchange_client = test_client(Meta { universe: vec![] });
+        let builder = BuilderInfo {
+            builder: "0x1111111111111111111111111111111111111111".to_string(),
+            fee: 10,
+        };
+        exchange_client
+            .approved_builder_fees
+            .lock()
+            .unwrap()
+            .insert(Address::repeat_byte(0x11), 20);
+        exchange_client.check_builder_fee(&builder).await?;
+        assert_eq!(
+            exchange_client.cached_builder_fee(Address::repeat_byte(0x11)),
+            Some(20)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn accepted_orders_without_an_oid_are_not_errors() -> Result<()> {
+        type Client = ExchangeClient<PrivateKeySigner>;
+        assert_eq!(
+            Client::placed_order_status(Some(&ExchangeDataStatus::WaitingForTrigger))?,
+            OrderWaitStatus::Accepted {
+                status: "waitingForTrigger".to_string()
+            }
+        );
+        assert_eq!(
+            Client::placed_order_status(Some(&ExchangeDataStatus::Resting(RestingOrder {
+                oid: 7
+            })))?,
+            OrderWaitStatus::Resting { oid: 7 }
+        );
+        assert!(Client::placed_order_status(None).is_err());
+
+        assert_eq!(Client::order_update_status(7, "triggered"), None);
+        assert_eq!(
+            Client::order_update_status(7, "filled"),
+            Some(OrderWaitStatus::Filled { oid: 7 })
+        );
+        assert_eq!(
+            Client::order_update_status(7, "marginCanceled"),
+            Some(OrderWaitStatus::Closed {
+                oid: 7,
+                status: "marginCanceled".to_string()
+            })
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn batches_stop_at_the_first_failed_action() {
+        let exchange_client = test_client(Meta { universe: vec![] });
+        let cancel = || ClientCancelRequest {
+            asset: "ETH".to_string(),
+            oid: 1,
+        };
+        let batch = ActionBatch::new()
+            .cancels(vec![cancel()])
+            .cancels(vec![cancel()]);
+        let responses = exchange_client.send_batch(batch, None).await;
+        assert_eq!(responses.len(), 1);
+        assert!(matches!(responses[0], Err(Error::AssetNotFound)));
+    }
+
+    // Expected values were computed outside this crate with the Python SDK's algorithm:
+    // `action_hash` over the msgpacked `[multiSigUser, outerSigner, action]` envelope, then
+    // `sign_l1_action` on mainnet
+    #[tokio::test]
+    async fn multi_sig_envelopes_hash_and_sign_like_the_python_sdk() -> Result<()> {
+        let exchange_client = test_client(Meta { universe: vec![] });
+        let wallet: PrivateKeySigner =
+            "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+                .parse()
+                .unwrap();
+        let (multi_sig_user, outer_signer) =
+            (Address::repeat_byte(0x11), Address::repeat_byte(0x22));
+        let action = Actions::ScheduleCancel(ScheduleCancel {
+            time: Some(1700000000000),
+        });
+        let nonce = 1700000000000;
+
+        let envelope = (
+            multi_sig_user.to_string().to_lowercase(),
+            outer_signer.to_string().to_lowercase(),
+            &action,
+        );
+        assert_eq!(
+            hash_action(&envelope, nonce, None)?,
+            "0xa6bc1ecdba33cfbf6cca663b999b8798c058bbbf602b05af7c5409beba13d0da"
+                .parse::<B256>()
+                .unwrap()
+        );
+
+        let signature = exchange_client
+            .sign_multi_sig_action(multi_sig_user, outer_signer, &action, nonce, Some(&wallet))
+            .await?;
+        let signature = MultiSigSignature::from(&signature);
+        assert_eq!(
+            signature.r,
+            "0x4e4ca54055cb579ec1f65861b787371e936391a09a231492e44989fc81299163"
+        );
+        assert_eq!(
+            signature.s,
+            "0x3c2ffe1affddf10d8dc28429069a913788b6497fa6ccce0770b222c70cb03554"
+        );
+        assert_eq!(signature.v, 28);
+        Ok(())
+    }
+
+    #[test]
+    fn info_client_follows_http_client() {
+        let mut exchange_client = test_client(Meta { universe: vec![] });
+        exchange_client.http_client = HttpClient::new(Client::new(), BaseUrl::Localhost.get_url());
+        exchange_client.info_client =
+            InfoClient::from_http_client(exchange_client.http_client.clone());
+        let exchange_client = exchange_client.with_timeout(Duration::from_secs(3));
+        assert_eq!(
+            exchange_client.info_client.http_client.base_url,
+            BaseUrl::Localhost.get_url()
+        );
+        assert_eq!(
+            exchange_client.info_client.http_client.timeout,
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[test]
+    fn agent_clients_act_on_the_master_account() {
+        let master = test_client(Meta { universe: vec![] });
+        let agent = master.with_signer(PrivateKeySigner::random());
+        assert_eq!(agent.user(None), master.wallet.address());
+        assert_eq!(agent.user(Some(&agent.wallet)), master.wallet.address());
+
+        let account = Address::repeat_byte(1);
+        let agent = agent.with_account_address(account);
+        assert_eq!(agent.user(None), account);
+    }
+
+    #[test]
+    fn spot_decimals_come_from_the_base_token() {
+        let spot_meta: SpotMeta = serde_json::from_str(
+            r#"{"universe":[{"tokens":[1,0],"name":"@1","index":1,"isCanonical":false}],
+                "tokens":[{"name":"USDC","szDecimals":8,"weiDecimals":8,"index":0,"tokenId":"0x6d1e7cde53ba9467b783cb7c530ce054","isCanonical":true},
+                          {"name":"HFUN","szDecimals":2,"weiDecimals":8,"index":1,"tokenId":"0xbaf265ef389da684513d98d68edf4eae","isCanonical":false}]}"#,
+        )
+        .unwrap();
+        let meta = Meta { universe: vec![] };
+        let mut exchange_client = test_client(meta.clone());
+        exchange_client.coin_to_asset =
+            ExchangeClient::<PrivateKeySigner>::coin_to_asset(&meta, &spot_meta);
+        exchange_client.spot_pairs = ExchangeClient::<PrivateKeySigner>::spot_pairs(&spot_meta);
+
+        assert_eq!(exchange_client.spot_pairs[&10001], ("@1".to_string(), 2));
+        assert_eq!(exchange_client.asset_decimals("HFUN/USDC").unwrap(), (2, 6));
+        assert_eq!(
+            exchange_client.round_price("@1", 1.23456789).unwrap(),
+            1.2346
+        );
+        assert!(matches!(
+            exchange_client.asset_decimals("PURR/USDC"),
+            Err(Error::AssetNotFound)
+        ));
+    }
+
+    #[test]
+    fn orders_for_unknown_assets_fail_validation() {
+        let exchange_client = test_client(Meta { universe: vec![] });
+        let order = ClientOrderRequest::limit("BTC", Side::Buy, 50000., 0.1);
+        assert!(matches!(
+            exchange_client.validate_order(&order),
+            Err(Error::AssetNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn agents_rotated_before_they_are_valid_are_rejected() {
+        let exchange_client = std::sync::Arc::new(test_client(Meta { universe: vec![] }));
+        let valid_for = Duration::from_secs(3600);
+        assert!(matches!(
+            exchange_client
+                .rotating_agent("bot".to_string(), valid_for, valid_for)
+                .await,
+            Err(Error::InvalidRotationInterval { .. })
+        ));
+    }
 }
@@ -1,4 +1,7 @@
+use crate::Error;
+use alloy::primitives::Address;
 use serde::Deserialize;
+use serde_json::Value;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct RestingOrder {
@@ -30,10 +33,40 @@ pub struct ExchangeDataStatuses {
 }
 
 #[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "RawExchangeResponse")]
 pub struct ExchangeResponse {
-    #[serde(rename = "type")]
     pub response_type: String,
     pub data: Option<ExchangeDataStatuses>,
+    /// The untyped `data` field, for responses whose data is not a list of order statuses
+    pub raw_data: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct RawExchangeResponse {
+    #[serde(rename = "type")]
+    response_type: String,
+    data: Option<Value>,
+}
+
+// Data of another shape, e.g. a twap status, is only kept raw, but malformed order statuses fail
+// the whole response instead of passing for a response without data
+impl TryFrom<RawExchangeResponse> for ExchangeResponse {
+    type Error = String;
+
+    fn try_from(raw: RawExchangeResponse) -> Result<ExchangeResponse, String> {
+        let data = match &raw.data {
+            Some(data) if data.get("statuses").is_some() => Some(
+                serde_json::from_value(data.clone())
+                    .map_err(|e| format!("Invalid order statuses: {e}"))?,
+            ),
+            _ => None,
+        };
+        Ok(ExchangeResponse {
+            response_type: raw.response_type,
+            data,
+            raw_data: raw.data,
+        })
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -43,3 +76,301 @@ pub enum ExchangeResponseStatus {
     Ok(ExchangeResponse),
     Err(String),
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExchangeError {
+    MinimumValue,
+    InsufficientMargin,
+    PostOnlyWouldMatch,
+    ReduceOnlyWouldIncrease,
+    PriceTooFarFromOracle,
+    RateLimited,
+    Other(String),
+}
+
+/// Where an order placed with `ExchangeClient::order_and_wait` ended up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderWaitStatus {
+    Rejected(String),
+    Filled {
+        oid: u64,
+    },
+    /// Still resting when the timeout expired
+    Resting {
+        oid: u64,
+    },
+    /// Removed from the book without filling, e.g. "canceled" or "marginCanceled"
+    Closed {
+        oid: u64,
+        status: String,
+    },
+    /// Accepted without an order id to follow, e.g. "waitingForTrigger" for a trigger order
+    Accepted {
+        status: String,
+    },
+}
+
+impl From<&str> for ExchangeError {
+    fn from(message: &str) -> ExchangeError {
+        // The exchange appends details such as the current bbo, so only the prefix is matched
+        let lowercase = message.to_lowercase();
+        if lowercase.starts_with("order must have minimum value") {
+            ExchangeError::MinimumValue
+        } else if lowercase.starts_with("insufficient margin") {
+            ExchangeError::InsufficientMargin
+        } else if lowercase.starts_with("post only order would have immediately matched") {
+            ExchangeError::PostOnlyWouldMatch
+        } else if lowercase.starts_with("reduce only order would increase position") {
+            ExchangeError::ReduceOnlyWouldIncrease
+        } else if lowercase.starts_with("order price cannot be more than") {
+            ExchangeError::PriceTooFarFromOracle
+        } else if lowercase.starts_with("too many") {
+            ExchangeError::RateLimited
+        } else {
+            ExchangeError::Other(message.to_string())
+        }
+    }
+}
+
+impl ExchangeResponseStatus {
+    // Statuses are returned in the same order as the submitted orders
+    pub(crate) fn statuses(&self) -> impl Iterator<Item = &ExchangeDataStatus> {
+        let statuses = match self {
+            ExchangeResponseStatus::Ok(response) => response.data.as_ref(),
+            ExchangeResponseStatus::Err(_) => None,
+        };
+        statuses.into_iter().flat_map(|data| &data.statuses)
+    }
+
+    /// All rejections in the response, including those of individual orders in a bulk request
+    pub fn errors(&self) -> Vec<ExchangeError> {
+        match self {
+            ExchangeResponseStatus::Err(e) => vec![ExchangeError::from(e.as_str())],
+            ExchangeResponseStatus::Ok(_) => self
+                .statuses()
+                .filter_map(|status| match status {
+                    ExchangeDataStatus::Error(e) => Some(ExchangeError::from(e.as_str())),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// The first rejection in the response, see `errors`
+    pub fn as_error(&self) -> Option<ExchangeError> {
+        self.errors().into_iter().next()
+    }
+
+    /// True if neither the request nor any of its orders were rejected
+    pub fn is_success(&self) -> bool {
+        self.errors().is_empty()
+    }
+
+    /// Turns the first rejection, of the request or of one of its orders, into
+    /// `Error::ExchangeRejected`. Use `errors` to handle partially accepted bulk requests
+    pub fn into_result(self) -> Result<ExchangeResponse, Error> {
+        match self {
+            ExchangeResponseStatus::Err(e) => Err(Error::ExchangeRejected(e)),
+            ExchangeResponseStatus::Ok(response) => {
+                let rejection = response
+                    .data
+                    .iter()
+                    .flat_map(|data| &data.statuses)
+                    .find_map(|status| match status {
+                        ExchangeDataStatus::Error(e) => Some(e.clone()),
+                        _ => None,
+                    });
+                match rejection {
+                    Some(e) => Err(Error::ExchangeRejected(e)),
+                    None => Ok(response),
+                }
+            }
+        }
+    }
+
+    pub fn resting_oids(&self) -> Vec<u64> {
+        self.statuses()
+            .filter_map(|status| match status {
+                ExchangeDataStatus::Resting(resting_order) => Some(resting_order.oid),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The id of a twap order that started running, used to cancel it
+    pub fn twap_id(&self) -> Option<u64> {
+        match self {
+            ExchangeResponseStatus::Ok(response) => response
+                .raw_data
+                .as_ref()?
+                .pointer("/status/running/twapId")?
+                .as_u64(),
+            ExchangeResponseStatus::Err(_) => None,
+        }
+    }
+
+    pub fn sub_account_address(&self) -> Option<Address> {
+        match self {
+            ExchangeResponseStatus::Ok(response) => {
+                response.raw_data.as_ref()?.as_str()?.parse().ok()
+            }
+            ExchangeResponseStatus::Err(_) => None,
+        }
+    }
+
+    pub fn filled_orders(&self) -> Vec<FilledOrder> {
+        self.statuses()
+            .filter_map(|status| match status {
+                ExchangeDataStatus::Filled(filled_order) => Some(filled_order.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+// Nonce rejections are retryable with a fresh nonce, so they are raised as errors of their own
+pub(crate) fn nonce_error(message: &str) -> Option<Error> {
+    let lowercase = message.to_lowercase();
+    if !lowercase.contains("nonce") {
+        return None;
+    }
+    if lowercase.contains("already used") || lowercase.contains("duplicate") {
+        Some(Error::NonceDuplicate(message.to_string()))
+    } else if lowercase.contains("must be larger than")
+        || lowercase.contains("too low")
+        || lowercase.contains("too old")
+    {
+        Some(Error::NonceTooOld(message.to_string()))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order_rejections_are_parsed() {
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[
+                {"error":"Order must have minimum value of $10."},
+                {"resting":{"oid":77738308}},
+                {"error":"Post only order would have immediately matched, bbo was 1.0@2.0. asset=0"},
+                {"error":"Insufficient margin to place order. asset=0"},
+                {"error":"Something new"}
+            ]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            response.errors(),
+            vec![
+                ExchangeError::MinimumValue,
+                ExchangeError::PostOnlyWouldMatch,
+                ExchangeError::InsufficientMargin,
+                ExchangeError::Other("Something new".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn request_rejections_are_parsed() {
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"err","response":"Too many cumulative requests sent (10001 > 10000) for cumulative volume traded $0.00."}"#,
+        )
+        .unwrap();
+        assert_eq!(response.as_error(), Some(ExchangeError::RateLimited));
+
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[
+                {"resting":{"oid":77738308}},
+                {"error":"Reduce only order would increase position. asset=0"},
+                {"error":"Order price cannot be more than 80% away from the reference price"}
+            ]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            response.as_error(),
+            Some(ExchangeError::ReduceOnlyWouldIncrease)
+        );
+        assert_eq!(response.errors()[1], ExchangeError::PriceTooFarFromOracle);
+        assert!(!response.is_success());
+        assert!(matches!(
+            response.into_result(),
+            Err(Error::ExchangeRejected(e)) if e.starts_with("Reduce only order")
+        ));
+
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[{"resting":{"oid":77738308}}]}}}"#,
+        )
+        .unwrap();
+        assert!(response.is_success());
+        assert!(response.into_result().is_ok());
+    }
+
+    #[test]
+    fn bulk_order_results_are_split_into_resting_and_filled() {
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[
+                {"resting":{"oid":77738308}},
+                {"filled":{"totalSz":"0.02","avgPx":"1891.4","oid":77747314}},
+                {"error":"Order must have minimum value of $10."},
+                {"resting":{"oid":77738309}}
+            ]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(response.resting_oids(), vec![77738308, 77738309]);
+        let filled_orders = response.filled_orders();
+        assert_eq!(filled_orders.len(), 1);
+        assert_eq!(filled_orders[0].oid, 77747314);
+        assert_eq!(filled_orders[0].avg_px, "1891.4");
+    }
+
+    #[test]
+    fn twap_id_is_read_from_running_status() {
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"twapOrder","data":{"status":{"running":{"twapId":77738308}}}}}"#,
+        )
+        .unwrap();
+        assert_eq!(response.twap_id(), Some(77738308));
+    }
+
+    #[test]
+    fn sub_account_address_is_read_from_data() {
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"createSubAccount","data":"0x0ba7f7255fd6a8af2ab9e4a4bb5e46a410c2b3d0"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            response.sub_account_address(),
+            "0x0ba7f7255fd6a8af2ab9e4a4bb5e46a410c2b3d0".parse().ok()
+        );
+    }
+
+    #[test]
+    fn nonce_rejections_are_classified() {
+        assert!(matches!(
+            nonce_error("Invalid nonce: duplicate nonce 1713825891591"),
+            Some(Error::NonceDuplicate(_))
+        ));
+        assert!(matches!(
+            nonce_error("Nonce 1713825891591 must be larger than 1713825891600"),
+            Some(Error::NonceTooOld(_))
+        ));
+        assert!(nonce_error("Insufficient margin to place order. asset=0").is_none());
+    }
+
+    #[test]
+    fn malformed_order_statuses_are_not_dropped() {
+        let twap: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"twapOrder","data":{"status":{"running":{"twapId":77}}}}}"#,
+        )
+        .unwrap();
+        assert_eq!(twap.twap_id(), Some(77));
+
+        assert!(serde_json::from_str::<ExchangeResponseStatus>(
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[{"resting":{"oid":"x"}}]}}}"#,
+        )
+        .is_err());
+    }
+}
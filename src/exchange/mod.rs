@@ -1,4 +1,6 @@
 mod actions;
+mod agent;
+mod batch;
 mod builder;
 mod cancel;
 mod exchange_client;
@@ -7,6 +9,8 @@ mod modify;
 mod order;
 
 pub use actions::*;
+pub use agent::RotatingAgent;
+pub use batch::ActionBatch;
 pub use builder::*;
 pub use cancel::{ClientCancelRequest, ClientCancelRequestCloid};
 pub use exchange_client::*;
@@ -14,5 +18,5 @@ pub use exchange_responses::*;
 pub use modify::{ClientModifyRequest, ModifyRequest};
 pub use order::{
     ClientLimit, ClientOrder, ClientOrderRequest, ClientTrigger, MarketCloseParams,
-    MarketOrderParams, Order,
+    MarketOrderParams, Order, OrderBuilder, PositionTpslParams, Side, Tif, TwapOrderParams,
 };
@@ -1,5 +1,7 @@
-use super::{order::OrderRequest, ClientOrderRequest};
+use super::{order::OrderRequest, ClientLimit, ClientOrder, ClientOrderRequest, Tif};
+use crate::{helpers::cloid_from_hex, prelude::*, Error, FrontendOpenOrdersResponse};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[derive(Debug)]
 pub struct ClientModifyRequest {
@@ -7,8 +9,78 @@ pub struct ClientModifyRequest {
     pub order: ClientOrderRequest,
 }
 
+impl ClientModifyRequest {
+    // Moves a resting limit order to a new price and size, keeping its oid, side, time in force,
+    // reduce only flag and cloid unless a new cloid is given
+    pub fn replace(
+        order: &FrontendOpenOrdersResponse,
+        limit_px: f64,
+        sz: f64,
+        cloid: Option<Uuid>,
+    ) -> Result<ClientModifyRequest> {
+        if order.is_trigger {
+            return Err(Error::OrderTypeNotFound);
+        }
+        let cloid = match (cloid, &order.cloid) {
+            (Some(cloid), _) => Some(cloid),
+            (None, Some(cloid)) => Some(cloid_from_hex(cloid)?),
+            (None, None) => None,
+        };
+        Ok(ClientModifyRequest {
+            oid: order.oid,
+            order: ClientOrderRequest {
+                asset: order.coin.clone(),
+                side: (order.side == "B").into(),
+                reduce_only: order.reduce_only,
+                limit_px,
+                sz,
+                cloid,
+                order_type: ClientOrder::Limit(ClientLimit {
+                    tif: match &order.tif {
+                        Some(tif) => tif.parse()?,
+                        None => Tif::Gtc,
+                    },
+                }),
+            },
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ModifyRequest {
     pub oid: u64,
     pub order: OrderRequest,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Side;
+
+    #[test]
+    fn replace_keeps_the_oid_and_order_settings() -> Result<()> {
+        let order: FrontendOpenOrdersResponse = serde_json::from_str(
+            r#"{"coin":"ETH","isPositionTpsl":false,"isTrigger":false,"limitPx":"1800.0","oid":77738308,
+                "orderType":"Limit","origSz":"0.1","reduceOnly":true,"side":"A","sz":"0.1","timestamp":1700000000000,
+                "triggerCondition":"N/A","triggerPx":"0.0","tif":"Alo","cloid":"0x1234567890abcdef1234567890abcdef"}"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        let modify = ClientModifyRequest::replace(&order, 1810.0, 0.2, None)?;
+        assert_eq!(modify.oid, 77738308);
+        assert_eq!(modify.order.side, Side::Sell);
+        assert!(modify.order.reduce_only);
+        assert_eq!(
+            modify.order.cloid.map(|cloid| cloid.simple().to_string()),
+            Some("1234567890abcdef1234567890abcdef".to_string())
+        );
+        assert!(
+            matches!(modify.order.order_type, ClientOrder::Limit(ref limit) if limit.tif == Tif::Alo)
+        );
+
+        let new_cloid = Uuid::new_v4();
+        let modify = ClientModifyRequest::replace(&order, 1810.0, 0.2, Some(new_cloid))?;
+        assert_eq!(modify.order.cloid, Some(new_cloid));
+        Ok(())
+    }
+}
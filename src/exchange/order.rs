@@ -1,13 +1,47 @@
 use crate::{
+    consts::{EPSILON, MAX_SIG_FIGS, PERP_MAX_DECIMALS, SPOT_MAX_DECIMALS},
     errors::Error,
-    helpers::{float_to_string_for_hashing, uuid_to_hex_string},
+    helpers::{
+        float_to_string_for_hashing, round_to_decimals, round_to_significant_and_decimal,
+        uuid_to_hex_string,
+    },
+    meta::Meta,
     prelude::*,
 };
 use alloy::signers::Signer;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt, str::FromStr};
 use uuid::Uuid;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    pub fn is_buy(self) -> bool {
+        self == Side::Buy
+    }
+}
+
+// `true` is a buy, matching the `is_buy` flags used throughout the API
+impl From<bool> for Side {
+    fn from(is_buy: bool) -> Self {
+        if is_buy {
+            Side::Buy
+        } else {
+            Side::Sell
+        }
+    }
+}
+
+impl From<Side> for bool {
+    fn from(side: Side) -> Self {
+        side.is_buy()
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Limit {
     pub tif: String,
@@ -47,9 +81,53 @@ pub struct OrderRequest {
     pub cloid: Option<String>,
 }
 
+/// Time in force of a limit order
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tif {
+    /// Good til canceled
+    #[serde(rename = "Gtc")]
+    Gtc,
+    /// Immediate or cancel
+    #[serde(rename = "Ioc")]
+    Ioc,
+    /// Add liquidity only, i.e. post only
+    #[serde(rename = "Alo")]
+    Alo,
+}
+
+impl Tif {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Tif::Gtc => "Gtc",
+            Tif::Ioc => "Ioc",
+            Tif::Alo => "Alo",
+        }
+    }
+}
+
+impl fmt::Display for Tif {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// Case insensitive, so "ioc" and "GTC" are accepted too
+impl FromStr for Tif {
+    type Err = Error;
+
+    fn from_str(tif: &str) -> Result<Tif> {
+        match tif.to_lowercase().as_str() {
+            "gtc" => Ok(Tif::Gtc),
+            "ioc" => Ok(Tif::Ioc),
+            "alo" => Ok(Tif::Alo),
+            _ => Err(Error::InvalidTif(tif.to_string())),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ClientLimit {
-    pub tif: String,
+    pub tif: Tif,
 }
 
 #[derive(Debug)]
@@ -62,14 +140,26 @@ pub struct ClientTrigger {
 #[derive(Debug)]
 pub struct MarketOrderParams<'a, T: Signer> {
     pub asset: &'a str,
-    pub is_buy: bool,
+    pub side: Side,
     pub sz: f64,
     pub px: Option<f64>,
     pub slippage: Option<f64>,
+    /// Worst acceptable fill price, used as the limit price instead of applying `slippage`
+    pub price_limit: Option<f64>,
     pub cloid: Option<Uuid>,
     pub wallet: Option<&'a T>,
 }
 
+#[derive(Debug)]
+pub struct TwapOrderParams<'a> {
+    pub asset: &'a str,
+    pub side: Side,
+    pub sz: f64,
+    pub reduce_only: bool,
+    pub minutes: u64,
+    pub randomize: bool,
+}
+
 #[derive(Debug)]
 pub struct MarketCloseParams<'a, T: Signer> {
     pub asset: &'a str,
@@ -80,6 +170,17 @@ pub struct MarketCloseParams<'a, T: Signer> {
     pub wallet: Option<&'a T>,
 }
 
+/// Targets the whole open position unless `sz` is set
+#[derive(Debug)]
+pub struct PositionTpslParams<'a, T: Signer> {
+    pub asset: &'a str,
+    pub trigger_px: f64,
+    pub sz: Option<f64>,
+    pub slippage: Option<f64>,
+    pub cloid: Option<Uuid>,
+    pub wallet: Option<&'a T>,
+}
+
 #[derive(Debug)]
 pub enum ClientOrder {
     Limit(ClientLimit),
@@ -89,18 +190,95 @@ pub enum ClientOrder {
 #[derive(Debug)]
 pub struct ClientOrderRequest {
     pub asset: String,
-    pub is_buy: bool,
+    pub side: Side,
     pub reduce_only: bool,
     pub limit_px: f64,
     pub sz: f64,
+    /// Sent as 0x followed by 32 hex digits, see `new_cloid` and `cloid_from_hex`
     pub cloid: Option<Uuid>,
     pub order_type: ClientOrder,
 }
 
 impl ClientOrderRequest {
+    /// A Gtc limit order; chain `post_only`, `ioc`, `reduce_only` or `cloid` to adjust it
+    pub fn limit(asset: &str, side: impl Into<Side>, limit_px: f64, sz: f64) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: asset.to_string(),
+            side: side.into(),
+            reduce_only: false,
+            limit_px,
+            sz,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        }
+    }
+
+    fn with_tif(mut self, tif: Tif) -> Self {
+        self.order_type = ClientOrder::Limit(ClientLimit { tif });
+        self
+    }
+
+    /// Add liquidity only: the order is canceled instead of matching immediately
+    pub fn post_only(self) -> Self {
+        self.with_tif(Tif::Alo)
+    }
+
+    pub fn ioc(self) -> Self {
+        self.with_tif(Tif::Ioc)
+    }
+
+    pub fn gtc(self) -> Self {
+        self.with_tif(Tif::Gtc)
+    }
+
+    pub fn reduce_only(mut self) -> Self {
+        self.reduce_only = true;
+        self
+    }
+
+    pub fn cloid(mut self, cloid: Uuid) -> Self {
+        self.cloid = Some(cloid);
+        self
+    }
+
+    // Integer prices are always accepted, whatever their number of significant figures.
+    // Zero and non-finite values are rejected with the smallest tick or lot as the nearest value
+    pub(crate) fn validate_decimals(&self, sz_decimals: u32, price_decimals: u32) -> Result<()> {
+        if self.limit_px == 0. || !self.limit_px.is_finite() {
+            return Err(Error::InvalidTickSize {
+                px: self.limit_px,
+                nearest: 10f64.powi(-(price_decimals as i32)),
+            });
+        }
+        if self.sz == 0. || !self.sz.is_finite() {
+            return Err(Error::InvalidLotSize {
+                sz: self.sz,
+                nearest: 10f64.powi(-(sz_decimals as i32)),
+            });
+        }
+        let nearest_px =
+            round_to_significant_and_decimal(self.limit_px, MAX_SIG_FIGS, price_decimals);
+        if self.limit_px.fract() != 0. && (nearest_px - self.limit_px).abs() > EPSILON {
+            return Err(Error::InvalidTickSize {
+                px: self.limit_px,
+                nearest: nearest_px,
+            });
+        }
+        let nearest_sz = round_to_decimals(self.sz, sz_decimals);
+        if (nearest_sz - self.sz).abs() > EPSILON {
+            return Err(Error::InvalidLotSize {
+                sz: self.sz,
+                nearest: nearest_sz,
+            });
+        }
+        Ok(())
+    }
+
     pub(crate) fn convert(self, coin_to_asset: &HashMap<String, u32>) -> Result<OrderRequest> {
         let order_type = match self.order_type {
-            ClientOrder::Limit(limit) => Order::Limit(Limit { tif: limit.tif }),
+            ClientOrder::Limit(limit) => Order::Limit(Limit {
+                tif: limit.tif.to_string(),
+            }),
             ClientOrder::Trigger(trigger) => Order::Trigger(Trigger {
                 trigger_px: float_to_string_for_hashing(trigger.trigger_px),
                 is_market: trigger.is_market,
@@ -113,7 +291,7 @@ impl ClientOrderRequest {
 
         Ok(OrderRequest {
             asset,
-            is_buy: self.is_buy,
+            is_buy: self.side.is_buy(),
             reduce_only: self.reduce_only,
             limit_px: float_to_string_for_hashing(self.limit_px),
             sz: float_to_string_for_hashing(self.sz),
@@ -122,3 +300,237 @@ impl ClientOrderRequest {
         })
     }
 }
+
+/// Builds a [`ClientOrderRequest`] whose price and size are always valid for the asset's tick and lot size.
+#[derive(Debug)]
+pub struct OrderBuilder {
+    asset: String,
+    sz_decimals: u32,
+    max_decimals: u32,
+    side: Side,
+    reduce_only: bool,
+    limit_px: f64,
+    sz: f64,
+    cloid: Option<Uuid>,
+    order_type: ClientOrder,
+}
+
+impl OrderBuilder {
+    pub fn new(asset: &str, meta: &Meta) -> Result<OrderBuilder> {
+        let asset_meta = meta
+            .universe
+            .iter()
+            .find(|a| a.name == asset)
+            .ok_or(Error::AssetNotFound)?;
+        Ok(Self::with_decimals(
+            asset,
+            asset_meta.sz_decimals,
+            PERP_MAX_DECIMALS,
+        ))
+    }
+
+    pub fn spot(asset: &str, sz_decimals: u32) -> OrderBuilder {
+        Self::with_decimals(asset, sz_decimals, SPOT_MAX_DECIMALS)
+    }
+
+    fn with_decimals(asset: &str, sz_decimals: u32, max_decimals: u32) -> OrderBuilder {
+        OrderBuilder {
+            asset: asset.to_string(),
+            sz_decimals,
+            max_decimals,
+            side: Side::Buy,
+            reduce_only: false,
+            limit_px: 0.0,
+            sz: 0.0,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        }
+    }
+
+    fn price_decimals(&self) -> u32 {
+        self.max_decimals.saturating_sub(self.sz_decimals)
+    }
+
+    pub fn buy(mut self) -> Self {
+        self.side = Side::Buy;
+        self
+    }
+
+    pub fn sell(mut self) -> Self {
+        self.side = Side::Sell;
+        self
+    }
+
+    pub fn side(mut self, side: Side) -> Self {
+        self.side = side;
+        self
+    }
+
+    pub fn price(mut self, px: f64) -> Self {
+        self.limit_px = round_to_significant_and_decimal(px, MAX_SIG_FIGS, self.price_decimals());
+        self
+    }
+
+    pub fn size(mut self, sz: f64) -> Self {
+        self.sz = round_to_decimals(sz, self.sz_decimals);
+        self
+    }
+
+    pub fn reduce_only(mut self, reduce_only: bool) -> Self {
+        self.reduce_only = reduce_only;
+        self
+    }
+
+    pub fn cloid(mut self, cloid: Uuid) -> Self {
+        self.cloid = Some(cloid);
+        self
+    }
+
+    pub fn order_type(mut self, order_type: ClientOrder) -> Self {
+        self.order_type = order_type;
+        self
+    }
+
+    pub fn build(self) -> ClientOrderRequest {
+        let price_decimals = self.price_decimals();
+        let order_type = match self.order_type {
+            ClientOrder::Trigger(trigger) => ClientOrder::Trigger(ClientTrigger {
+                trigger_px: round_to_significant_and_decimal(
+                    trigger.trigger_px,
+                    MAX_SIG_FIGS,
+                    price_decimals,
+                ),
+                ..trigger
+            }),
+            limit => limit,
+        };
+        ClientOrderRequest {
+            asset: self.asset,
+            side: self.side,
+            reduce_only: self.reduce_only,
+            limit_px: self.limit_px,
+            sz: self.sz,
+            cloid: self.cloid,
+            order_type,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order_builder_rounds_to_tick_and_lot() {
+        let meta: Meta = serde_json::from_str(
+            r#"{"universe":[{"name":"ETH","szDecimals":4,"maxLeverage":50}]}"#,
+        )
+        .unwrap();
+        let order = OrderBuilder::new("ETH", &meta)
+            .unwrap()
+            .sell()
+            .price(1834.56789)
+            .size(0.123456)
+            .build();
+        assert_eq!(order.limit_px, 1834.6);
+        assert_eq!(order.sz, 0.1235);
+        assert_eq!(order.side, Side::Sell);
+        assert!(OrderBuilder::new("BTC", &meta).is_err());
+    }
+
+    #[test]
+    fn limit_order_helpers_set_tif_and_reject_unknown_ones() {
+        let coin_to_asset = HashMap::from([("ETH".to_string(), 1)]);
+        let order = ClientOrderRequest::limit("ETH", true, 1800.5, 0.1)
+            .post_only()
+            .reduce_only()
+            .convert(&coin_to_asset)
+            .unwrap();
+        assert!(order.reduce_only);
+        assert!(matches!(order.order_type, Order::Limit(Limit { tif }) if tif == "Alo"));
+
+        assert_eq!("alo".parse::<Tif>().unwrap(), Tif::Alo);
+        assert_eq!("GTC".parse::<Tif>().unwrap(), Tif::Gtc);
+        assert!(matches!(
+            "FrontendMarket".parse::<Tif>(),
+            Err(Error::InvalidTif(tif)) if tif == "FrontendMarket"
+        ));
+    }
+
+    #[test]
+    fn trigger_orders_use_the_trigger_wire_format() {
+        let coin_to_asset = HashMap::from([("ETH".to_string(), 1)]);
+        let order = ClientOrderRequest {
+            asset: "ETH".to_string(),
+            side: Side::Sell,
+            reduce_only: true,
+            limit_px: 1700.0,
+            sz: 0.5,
+            cloid: None,
+            order_type: ClientOrder::Trigger(ClientTrigger {
+                is_market: true,
+                trigger_px: 1750.0,
+                tpsl: "sl".to_string(),
+            }),
+        }
+        .convert(&coin_to_asset)
+        .unwrap();
+        assert_eq!(
+            serde_json::to_string(&order).unwrap(),
+            r#"{"a":1,"b":false,"p":"1700","s":"0.5","r":true,"t":{"trigger":{"isMarket":true,"triggerPx":"1750","tpsl":"sl"}}}"#
+        );
+    }
+
+    #[test]
+    fn off_tick_prices_and_off_lot_sizes_are_rejected() {
+        let order = ClientOrderRequest::limit("ETH", true, 1834.5, 0.1234);
+        assert!(order.validate_decimals(4, 2).is_ok());
+        assert!(ClientOrderRequest::limit("BTC", true, 123456., 0.1)
+            .validate_decimals(5, 1)
+            .is_ok());
+        assert!(matches!(
+            ClientOrderRequest::limit("ETH", true, 1834.567, 0.1).validate_decimals(4, 2),
+            Err(Error::InvalidTickSize { nearest, .. }) if nearest == 1834.6
+        ));
+        assert!(matches!(
+            ClientOrderRequest::limit("ETH", true, 1834.5, 0.12345).validate_decimals(4, 2),
+            Err(Error::InvalidLotSize { nearest, .. }) if nearest == 0.1235
+        ));
+    }
+
+    #[test]
+    fn tifs_serialize_to_the_exchange_strings() {
+        for (tif, json) in [
+            (Tif::Gtc, r#""Gtc""#),
+            (Tif::Ioc, r#""Ioc""#),
+            (Tif::Alo, r#""Alo""#),
+        ] {
+            assert_eq!(serde_json::to_string(&tif).unwrap(), json);
+            assert_eq!(serde_json::from_str::<Tif>(json).unwrap(), tif);
+        }
+    }
+
+    #[test]
+    fn sides_and_buy_flags_are_interchangeable() {
+        assert_eq!(Side::from(true), Side::Buy);
+        assert!(!bool::from(Side::Sell));
+        let order = ClientOrderRequest::limit("ETH", false, 1800.5, 0.1);
+        assert_eq!(order.side, Side::Sell);
+    }
+
+    #[test]
+    fn zero_and_non_finite_orders_are_rejected() {
+        assert!(matches!(
+            ClientOrderRequest::limit("ETH", true, 0.0, 0.1).validate_decimals(4, 2),
+            Err(Error::InvalidTickSize { nearest, .. }) if nearest == 0.01
+        ));
+        assert!(matches!(
+            ClientOrderRequest::limit("ETH", true, f64::NAN, 0.1).validate_decimals(4, 2),
+            Err(Error::InvalidTickSize { .. })
+        ));
+        assert!(matches!(
+            ClientOrderRequest::limit("ETH", true, 1834.5, f64::INFINITY).validate_decimals(4, 2),
+            Err(Error::InvalidLotSize { nearest, .. }) if nearest == 0.0001
+        ));
+    }
+}
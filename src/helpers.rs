@@ -3,25 +3,34 @@ use chrono::prelude::Utc;
 use lazy_static::lazy_static;
 use log::info;
 use rand::{thread_rng, Rng};
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
 use uuid::Uuid;
 
-fn now_timestamp_ms() -> u64 {
+pub(crate) fn now_timestamp_ms() -> u64 {
     let now = Utc::now();
     now.timestamp_millis() as u64
 }
 
 pub(crate) fn next_nonce() -> u64 {
-    let nonce = CUR_NONCE.fetch_add(1, Ordering::Relaxed);
     let now_ms = now_timestamp_ms();
-    if nonce > now_ms + 1000 {
-        info!("nonce progressed too far ahead {nonce} {now_ms}");
-    }
-    // more than 300 seconds behind
-    if nonce + 300000 < now_ms {
-        CUR_NONCE.fetch_max(now_ms, Ordering::Relaxed);
+    let mut cur_nonce = CUR_NONCE.load(Ordering::Relaxed);
+    loop {
+        // Strictly increasing, and caught up to wall-clock time whenever it has fallen behind it
+        let nonce = (cur_nonce + 1).max(now_ms);
+        match CUR_NONCE.compare_exchange_weak(cur_nonce, nonce, Ordering::SeqCst, Ordering::Relaxed)
+        {
+            Ok(_) => {
+                if nonce > now_ms + 1000 {
+                    info!("nonce progressed too far ahead {nonce} {now_ms}");
+                }
+                return nonce;
+            }
+            Err(actual) => cur_nonce = actual,
+        }
     }
-    nonce
 }
 
 pub(crate) const WIRE_DECIMALS: u8 = 8;
@@ -41,7 +50,22 @@ pub(crate) fn float_to_string_for_hashing(x: f64) -> String {
     }
 }
 
-pub(crate) fn uuid_to_hex_string(uuid: Uuid) -> String {
+/// A random client order id, see `ClientOrderRequest::cloid`
+pub fn new_cloid() -> Uuid {
+    Uuid::new_v4()
+}
+
+/// Parses a cloid as returned by the exchange, 32 hex digits with or without a 0x prefix
+pub fn cloid_from_hex(cloid: &str) -> Result<Uuid> {
+    let hex = cloid.strip_prefix("0x").unwrap_or(cloid);
+    if hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::InvalidCloid(cloid.to_string()));
+    }
+    Uuid::parse_str(hex).map_err(|_| Error::InvalidCloid(cloid.to_string()))
+}
+
+/// The cloid as the exchange expects it, 0x followed by 32 lowercase hex digits
+pub fn uuid_to_hex_string(uuid: Uuid) -> String {
     let hex_string = uuid
         .as_bytes()
         .iter()
@@ -68,6 +92,45 @@ pub fn truncate_float(float: f64, decimals: u32, round_up: bool) -> f64 {
     float as f64 / pow10
 }
 
+pub fn round_to_decimals(value: f64, decimals: u32) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    (value * factor).round() / factor
+}
+
+// Zero, NaN and infinite values have no magnitude and are returned unchanged
+pub fn round_to_significant_and_decimal(value: f64, sig_figs: u32, max_decimals: u32) -> f64 {
+    if value == 0.0 || !value.is_finite() {
+        return value;
+    }
+    let abs_value = value.abs();
+    let magnitude = abs_value.log10().floor() as i32;
+    let scale = 10f64.powi(sig_figs as i32 - magnitude - 1);
+    let rounded = (abs_value * scale).round() / scale;
+    round_to_decimals(rounded.copysign(value), max_decimals)
+}
+
+// Rounds up or down to the nearest price that satisfies both the significant figure and decimal limits
+pub fn round_to_significant_and_decimal_directed(
+    value: f64,
+    sig_figs: u32,
+    max_decimals: u32,
+    round_up: bool,
+) -> f64 {
+    if value == 0.0 || !value.is_finite() {
+        return value;
+    }
+    let magnitude = value.abs().log10().floor() as i32;
+    let decimals = (sig_figs as i32 - magnitude - 1).min(max_decimals as i32);
+    let scaled = value * 10f64.powi(decimals);
+    // Tolerate float noise so a price already on a tick is not moved a full tick away
+    let rounded = if round_up {
+        (scaled - 1e-9).ceil()
+    } else {
+        (scaled + 1e-9).floor()
+    };
+    round_to_decimals(rounded / 10f64.powi(decimals), decimals.max(0) as u32)
+}
+
 pub fn bps_diff(x: f64, y: f64) -> u16 {
     if x.abs() < EPSILON {
         INF_BPS
@@ -93,6 +156,48 @@ impl BaseUrl {
     }
 }
 
+// A token or USDC amount as the exchange expects it in transfers: a plain decimal
+// with at most 8 decimals and no trailing zeros
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Amount(String);
+
+impl Amount {
+    pub fn from_f64(amount: f64) -> Amount {
+        Amount(float_to_string_for_hashing(amount))
+    }
+
+    // Class transfers and isolated margin updates take USDC as an integer number of millionths
+    pub(crate) fn to_micro_usdc(&self) -> Result<i64> {
+        let amount = self.0.parse::<f64>().map_err(|_| Error::FloatStringParse)?;
+        Ok((amount * 1e6).round() as i64)
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+// Strings are passed through unchanged, as before `Amount` existed
+impl From<&str> for Amount {
+    fn from(amount: &str) -> Amount {
+        Amount(amount.to_string())
+    }
+}
+
+impl From<String> for Amount {
+    fn from(amount: String) -> Amount {
+        Amount(amount)
+    }
+}
+
+impl From<f64> for Amount {
+    fn from(amount: f64) -> Amount {
+        Amount::from_f64(amount)
+    }
+}
+
 lazy_static! {
     static ref CUR_NONCE: AtomicU64 = AtomicU64::new(now_timestamp_ms());
 }
@@ -101,6 +206,46 @@ lazy_static! {
 mod tests {
     use super::*;
 
+    #[test]
+    fn cloids_round_trip_through_hex() {
+        let cloid = new_cloid();
+        let hex = uuid_to_hex_string(cloid);
+        assert_eq!(hex.len(), 34);
+        assert_eq!(cloid_from_hex(&hex).unwrap(), cloid);
+        assert_eq!(cloid_from_hex(&hex[2..]).unwrap(), cloid);
+        assert!(matches!(
+            cloid_from_hex("0x1234"),
+            Err(Error::InvalidCloid(_))
+        ));
+        assert!(cloid_from_hex("0x1234567890abcdef-234567890abcdef").is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn next_nonce_is_unique_and_increasing_across_tasks() {
+        let tasks: Vec<_> = (0..1000)
+            .map(|_| tokio::spawn(async { (next_nonce(), next_nonce()) }))
+            .collect();
+        let mut nonces = std::collections::HashSet::new();
+        for task in tasks {
+            let (first, second) = task.await.unwrap();
+            assert!(second > first);
+            assert!(nonces.insert(first));
+            assert!(nonces.insert(second));
+        }
+        assert_eq!(nonces.len(), 2000);
+    }
+
+    #[test]
+    fn amounts_are_formatted_for_the_exchange() {
+        assert_eq!(Amount::from_f64(1.5).to_string(), "1.5");
+        assert_eq!(Amount::from_f64(100.0).to_string(), "100");
+        assert_eq!(Amount::from_f64(0.123456789).to_string(), "0.12345679");
+        assert_eq!(Amount::from("2.50").to_string(), "2.50");
+        assert_eq!(Amount::from("2.50").to_micro_usdc().unwrap(), 2_500_000);
+        assert_eq!(Amount::from(-0.1).to_micro_usdc().unwrap(), -100_000);
+        assert!(Amount::from("ten").to_micro_usdc().is_err());
+    }
+
     #[test]
     fn float_to_string_for_hashing_test() {
         assert_eq!(float_to_string_for_hashing(0.), "0".to_string());
@@ -141,4 +286,54 @@ mod tests {
             "987654321".to_string()
         );
     }
+
+    #[test]
+    fn directed_rounding_never_crosses_less_than_requested() {
+        // An asset with szDecimals 2 accepts at most 4 decimals and 5 significant figures
+        let (sig_figs, max_decimals) = (MAX_SIG_FIGS, PERP_MAX_DECIMALS - 2);
+        for mid in [
+            0.123456789,
+            1.987654,
+            23.45678,
+            1834.56789,
+            65432.1,
+            123456.7,
+        ] {
+            let buy_px = mid * 1.05;
+            let rounded =
+                round_to_significant_and_decimal_directed(buy_px, sig_figs, max_decimals, true);
+            assert!(rounded >= buy_px, "{rounded} < {buy_px}");
+            assert_eq!(
+                rounded,
+                round_to_significant_and_decimal(rounded, sig_figs, max_decimals)
+            );
+
+            let sell_px = mid * 0.95;
+            let rounded =
+                round_to_significant_and_decimal_directed(sell_px, sig_figs, max_decimals, false);
+            assert!(rounded <= sell_px, "{rounded} > {sell_px}");
+            assert_eq!(
+                rounded,
+                round_to_significant_and_decimal(rounded, sig_figs, max_decimals)
+            );
+        }
+        assert_eq!(
+            round_to_significant_and_decimal_directed(1834.6, sig_figs, max_decimals, true),
+            1834.6
+        );
+    }
+
+    #[test]
+    fn rounding_passes_through_values_without_a_magnitude() {
+        assert_eq!(round_to_significant_and_decimal(0.0, MAX_SIG_FIGS, 4), 0.0);
+        assert_eq!(
+            round_to_significant_and_decimal_directed(0.0, MAX_SIG_FIGS, 4, true),
+            0.0
+        );
+        assert!(round_to_significant_and_decimal(f64::NAN, MAX_SIG_FIGS, 4).is_nan());
+        assert_eq!(
+            round_to_significant_and_decimal_directed(f64::INFINITY, MAX_SIG_FIGS, 4, false),
+            f64::INFINITY
+        );
+    }
 }
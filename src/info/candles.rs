@@ -0,0 +1,166 @@
+use crate::{helpers::float_to_string_for_hashing, prelude::*, CandleData, Error, Trade};
+
+// Calendar months have no fixed length, so "1M" candles cannot be built from trades
+fn interval_ms(interval: &str) -> Option<u64> {
+    let minute = 60_000;
+    let ms = match interval {
+        "1m" => minute,
+        "3m" => 3 * minute,
+        "5m" => 5 * minute,
+        "15m" => 15 * minute,
+        "30m" => 30 * minute,
+        "1h" => 60 * minute,
+        "2h" => 2 * 60 * minute,
+        "4h" => 4 * 60 * minute,
+        "8h" => 8 * 60 * minute,
+        "12h" => 12 * 60 * minute,
+        "1d" => 24 * 60 * minute,
+        "3d" => 3 * 24 * 60 * minute,
+        "1w" => 7 * 24 * 60 * minute,
+        _ => return None,
+    };
+    Some(ms)
+}
+
+#[derive(Debug)]
+struct OpenCandle {
+    time_open: u64,
+    open: String,
+    high: (f64, String),
+    low: (f64, String),
+    close: String,
+    volume: f64,
+    num_trades: u64,
+}
+
+// Builds candles of one coin from its trades, buckets are aligned to multiples of the interval
+// since the epoch like the exchange's own candles
+#[derive(Debug)]
+pub(crate) struct CandleAggregator {
+    coin: String,
+    interval: String,
+    interval_ms: u64,
+    current: Option<OpenCandle>,
+}
+
+impl CandleAggregator {
+    pub(crate) fn new(coin: &str, interval: &str) -> Result<CandleAggregator> {
+        let interval_ms = interval_ms(interval)
+            .ok_or_else(|| Error::InvalidCandleInterval(interval.to_string()))?;
+        Ok(CandleAggregator {
+            coin: coin.to_string(),
+            interval: interval.to_string(),
+            interval_ms,
+            current: None,
+        })
+    }
+
+    // Returns the candle the trade falls into, trades older than the current candle are ignored
+    pub(crate) fn update(&mut self, trade: &Trade) -> Result<Option<CandleData>> {
+        if trade.coin != self.coin {
+            return Ok(None);
+        }
+        let px = trade
+            .px
+            .parse::<f64>()
+            .map_err(|_| Error::FloatStringParse)?;
+        let sz = trade
+            .sz
+            .parse::<f64>()
+            .map_err(|_| Error::FloatStringParse)?;
+        let time_open = trade.time - trade.time % self.interval_ms;
+
+        match &mut self.current {
+            Some(candle) if candle.time_open > time_open => return Ok(None),
+            Some(candle) if candle.time_open == time_open => {
+                if px > candle.high.0 {
+                    candle.high = (px, trade.px.clone());
+                }
+                if px < candle.low.0 {
+                    candle.low = (px, trade.px.clone());
+                }
+                candle.close = trade.px.clone();
+                candle.volume += sz;
+                candle.num_trades += 1;
+            }
+            _ => {
+                self.current = Some(OpenCandle {
+                    time_open,
+                    open: trade.px.clone(),
+                    high: (px, trade.px.clone()),
+                    low: (px, trade.px.clone()),
+                    close: trade.px.clone(),
+                    volume: sz,
+                    num_trades: 1,
+                })
+            }
+        }
+        Ok(self.current.as_ref().map(|candle| self.candle_data(candle)))
+    }
+
+    fn candle_data(&self, candle: &OpenCandle) -> CandleData {
+        CandleData {
+            time_close: candle.time_open + self.interval_ms - 1,
+            close: candle.close.clone(),
+            high: candle.high.1.clone(),
+            interval: self.interval.clone(),
+            low: candle.low.1.clone(),
+            num_trades: candle.num_trades,
+            open: candle.open.clone(),
+            coin: self.coin.clone(),
+            time_open: candle.time_open,
+            volume: float_to_string_for_hashing(candle.volume),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(px: &str, sz: &str, time: u64) -> Trade {
+        Trade {
+            coin: "ETH".to_string(),
+            side: "B".to_string(),
+            px: px.to_string(),
+            sz: sz.to_string(),
+            time,
+            hash: String::new(),
+            tid: 0,
+        }
+    }
+
+    #[test]
+    fn trades_are_bucketed_by_interval() -> Result<()> {
+        let mut aggregator = CandleAggregator::new("ETH", "5m")?;
+        aggregator.update(&trade("1800.5", "0.1", 1_700_000_100_000))?;
+        aggregator.update(&trade("1810", "0.2", 1_700_000_200_000))?;
+        let candle = aggregator
+            .update(&trade("1795", "0.3", 1_700_000_250_000))?
+            .unwrap();
+        assert_eq!(candle.time_open, 1_700_000_100_000);
+        assert_eq!(candle.time_close, 1_700_000_399_999);
+        assert_eq!(
+            (candle.open, candle.high, candle.low, candle.close),
+            (
+                "1800.5".to_string(),
+                "1810".to_string(),
+                "1795".to_string(),
+                "1795".to_string()
+            )
+        );
+        assert_eq!(candle.volume, "0.6");
+        assert_eq!(candle.num_trades, 3);
+
+        let candle = aggregator
+            .update(&trade("1790", "1", 1_700_000_400_000))?
+            .unwrap();
+        assert_eq!(candle.time_open, 1_700_000_400_000);
+        assert_eq!(candle.num_trades, 1);
+        assert!(aggregator
+            .update(&trade("1780", "1", 1_700_000_399_000))?
+            .is_none());
+        assert!(CandleAggregator::new("ETH", "1M").is_err());
+        Ok(())
+    }
+}
@@ -1,16 +1,42 @@
 use crate::{
+    consts::CANDLE_INTERVALS,
+    helpers::uuid_to_hex_string,
     info::{
-        CandlesSnapshotResponse, FundingHistoryResponse, L2SnapshotResponse, OpenOrdersResponse,
-        OrderInfo, RecentTradesResponse, UserFillsResponse, UserStateResponse,
-    }, meta::{Meta, SpotMeta, SpotMetaAndAssetCtxs}, prelude::*, req::HttpClient, ws::{Subscription, WsManager}, BaseUrl, Error, FrontendOpenOrdersResponse, Message, OrderStatusResponse, ReferralResponse, UserFeesResponse, UserFundingResponse, UserTokenBalanceResponse
+        candles::CandleAggregator, AccountOverview, AllMidsResponse, CandlesSnapshotResponse,
+        FundingHistoryResponse, L2SnapshotResponse, MarketLeaders, MarketStats, OpenOrdersResponse,
+        OrderInfo, Portfolio, PredictedFunding, RecentTradesResponse, SubAccount,
+        UserFillsResponse, UserStateResponse,
+    },
+    meta::{Meta, SpotAssetContext, SpotMeta, SpotMetaAndAssetCtxs},
+    prelude::*,
+    req::{HttpClient, RateLimit, RetryPolicy},
+    ws::{replay, MessageSender, Recorder, Subscription, WsManager},
+    BaseUrl, Candle, DelegationResponse, DelegatorSummaryResponse, Error,
+    FrontendOpenOrdersResponse, LedgerUpdateData, Message, OrderStatusResponse, PerpsAssetCtx,
+    ReferralResponse, TokenDetailsResponse, UserFeesResponse, UserFundingResponse,
+    UserRateLimitResponse, UserTokenBalanceResponse, VaultDetailsResponse,
 };
 
 use alloy::primitives::Address;
-use log::info;
+use log::{debug, error, info};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tokio::sync::mpsc::UnboundedSender;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    hash::Hash,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    spawn,
+    sync::{
+        mpsc::{unbounded_channel, UnboundedSender},
+        Mutex,
+    },
+};
+use uuid::Uuid;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -21,6 +47,26 @@ pub struct CandleSnapshotRequest {
     end_time: u64,
 }
 
+/// Orders can be looked up by exchange order id or by client order id
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum OrderId {
+    Oid(u64),
+    Cloid(String),
+}
+
+impl From<u64> for OrderId {
+    fn from(oid: u64) -> OrderId {
+        OrderId::Oid(oid)
+    }
+}
+
+impl From<Uuid> for OrderId {
+    fn from(cloid: Uuid) -> OrderId {
+        OrderId::Cloid(uuid_to_hex_string(cloid))
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(tag = "type")]
 #[serde(rename_all = "camelCase")]
@@ -28,6 +74,8 @@ pub enum InfoRequest {
     #[serde(rename = "clearinghouseState")]
     UserState {
         user: Address,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        dex: Option<String>,
     },
     #[serde(rename = "batchClearinghouseStates")]
     UserStates {
@@ -48,16 +96,31 @@ pub enum InfoRequest {
     },
     OrderStatus {
         user: Address,
-        oid: u64,
+        oid: OrderId,
+    },
+    Meta {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        dex: Option<String>,
     },
-    Meta,
+    MetaAndAssetCtxs,
+    PredictedFundings,
+    PerpsAtOpenInterestCap,
     SpotMeta,
     SpotMetaAndAssetCtxs,
-    AllMids,
+    AllMids {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        dex: Option<String>,
+    },
     UserFills {
         user: Address,
     },
     #[serde(rename_all = "camelCase")]
+    UserFillsByTime {
+        user: Address,
+        start_time: u64,
+        end_time: Option<u64>,
+    },
+    #[serde(rename_all = "camelCase")]
     FundingHistory {
         coin: String,
         start_time: u64,
@@ -69,8 +132,19 @@ pub enum InfoRequest {
         start_time: u64,
         end_time: Option<u64>,
     },
+    #[serde(rename_all = "camelCase")]
+    UserNonFundingLedgerUpdates {
+        user: Address,
+        start_time: u64,
+        end_time: Option<u64>,
+    },
+    #[serde(rename_all = "camelCase")]
     L2Book {
         coin: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        n_sig_figs: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mantissa: Option<u32>,
     },
     RecentTrades {
         coin: String,
@@ -85,16 +159,53 @@ pub enum InfoRequest {
     HistoricalOrders {
         user: Address,
     },
+    UserRateLimit {
+        user: Address,
+    },
+    Portfolio {
+        user: Address,
+    },
+    MaxBuilderFee {
+        user: Address,
+        builder: Address,
+    },
+    SubAccounts {
+        user: Address,
+    },
+    #[serde(rename_all = "camelCase")]
+    VaultDetails {
+        vault_address: Address,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        user: Option<Address>,
+    },
+    Delegations {
+        user: Address,
+    },
+    DelegatorSummary {
+        user: Address,
+    },
+    #[serde(rename_all = "camelCase")]
+    TokenDetails {
+        token_id: String,
+    },
 }
 
-#[derive(Debug)]
+// Clones share the websocket connection and its subscriptions, which is closed once the last
+// clone is dropped
+#[derive(Debug, Clone)]
 pub struct InfoClient {
     pub http_client: HttpClient,
-    pub(crate) ws_manager: Option<WsManager>,
+    pub(crate) ws_manager: Arc<Mutex<Option<WsManager>>>,
     reconnect: bool,
+    ping_interval: Duration,
 }
 
 impl InfoClient {
+    const USER_FILLS_PAGE_SIZE: usize = 2000;
+    const FUNDING_PAGE_SIZE: usize = 500;
+    const LEDGER_UPDATES_PAGE_SIZE: usize = 500;
+    const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(30);
+
     pub async fn new(client: Option<Client>, base_url: Option<BaseUrl>) -> Result<InfoClient> {
         Self::new_internal(client, base_url, false).await
     }
@@ -115,51 +226,213 @@ impl InfoClient {
         let base_url = base_url.unwrap_or(BaseUrl::Mainnet).get_url();
 
         Ok(InfoClient {
-            http_client: HttpClient { client, base_url },
-            ws_manager: None,
             reconnect,
+            ..Self::from_http_client(HttpClient::new(client, base_url))
         })
     }
 
+    // Queries through `http_client`, sharing its timeout, retry policy and rate limiter
+    pub(crate) fn from_http_client(http_client: HttpClient) -> InfoClient {
+        InfoClient {
+            http_client,
+            ws_manager: Arc::new(Mutex::new(None)),
+            reconnect: false,
+            ping_interval: Self::DEFAULT_PING_INTERVAL,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> InfoClient {
+        self.http_client = self.http_client.with_timeout(timeout);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> InfoClient {
+        self.http_client = self.http_client.with_retry_policy(retry_policy);
+        self
+    }
+
+    // Off by default, requests are then sent as soon as they are made
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> InfoClient {
+        self.http_client = self.http_client.with_rate_limit(rate_limit);
+        self
+    }
+
+    // Takes effect for the websocket connection opened by the next subscription
+    pub fn with_ping_interval(mut self, ping_interval: Duration) -> InfoClient {
+        self.ping_interval = ping_interval;
+        self
+    }
+
+    // All subscriptions of one InfoClient share a single websocket connection, each feed is
+    // delivered only to the channels subscribed to it
     pub async fn subscribe(
-        &mut self,
+        &self,
         subscription: Subscription,
         sender_channel: UnboundedSender<Message>,
     ) -> Result<u32> {
-        if self.ws_manager.is_none() {
-            let ws_manager = WsManager::new(
-                format!("ws{}/ws", &self.http_client.base_url[4..]),
-                self.reconnect,
+        self.subscribe_internal(subscription, MessageSender::Plain(sender_channel), None)
+            .await
+    }
+
+    // Streams `Message::Candle` updates built locally from the trades feed, for candles the snapshot
+    // endpoint does not return yet. The first candle only covers trades since subscribing, pass
+    // the returned id to `unsubscribe` to stop it
+    pub async fn live_candles(
+        &self,
+        coin: &str,
+        interval: &str,
+        sender_channel: UnboundedSender<Message>,
+    ) -> Result<u32> {
+        let mut aggregator = CandleAggregator::new(coin, interval)?;
+        let (trades_sender, mut trades_receiver) = unbounded_channel();
+        let subscription_id = self
+            .subscribe(
+                Subscription::Trades {
+                    coin: coin.to_string(),
+                },
+                trades_sender,
             )
             .await?;
-            self.ws_manager = Some(ws_manager);
+
+        spawn(async move {
+            while let Some(message) = trades_receiver.recv().await {
+                let Message::Trades(trades) = message else {
+                    continue;
+                };
+                for trade in &trades.data {
+                    match aggregator.update(trade) {
+                        Ok(Some(data)) => {
+                            if sender_channel
+                                .send(Message::Candle(Candle { data }))
+                                .is_err()
+                            {
+                                return;
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(err) => error!("Could not aggregate trade {trade:?}: {err}"),
+                    }
+                }
+            }
+        });
+        Ok(subscription_id)
+    }
+
+    // Like `subscribe`, but every message arrives with the id of the subscription that produced it,
+    // so many subscriptions can share one channel
+    pub async fn subscribe_tagged(
+        &self,
+        subscription: Subscription,
+        sender_channel: UnboundedSender<(u32, Message)>,
+    ) -> Result<u32> {
+        self.subscribe_internal(subscription, MessageSender::Tagged(sender_channel), None)
+            .await
+    }
+
+    // Every raw frame delivered to this subscription is also appended to `path`, see `InfoClient::replay`
+    pub async fn subscribe_with_recording(
+        &self,
+        subscription: Subscription,
+        sender_channel: UnboundedSender<Message>,
+        path: impl AsRef<Path>,
+    ) -> Result<u32> {
+        let recorder = Recorder::create(path.as_ref())?;
+        self.subscribe_internal(
+            subscription,
+            MessageSender::Plain(sender_channel),
+            Some(recorder),
+        )
+        .await
+    }
+
+    /// Feeds a recording made with `subscribe_with_recording` into `sender_channel`.
+    /// A `speed` of 1.0 keeps the original timing, 2.0 replays twice as fast and `f64::INFINITY` without delay.
+    /// Zero, negative and NaN speeds are rejected with `Error::InvalidReplaySpeed`.
+    pub async fn replay(
+        path: impl AsRef<Path>,
+        sender_channel: UnboundedSender<Message>,
+        speed: f64,
+    ) -> Result<()> {
+        replay(path.as_ref(), &sender_channel, speed).await
+    }
+
+    async fn subscribe_internal(
+        &self,
+        subscription: Subscription,
+        sender_channel: MessageSender,
+        recorder: Option<Recorder>,
+    ) -> Result<u32> {
+        let identifier =
+            serde_json::to_string(&subscription).map_err(|e| Error::JsonParse(e.to_string()))?;
+        let mut ws_manager = self.ws_manager.lock().await;
+        self.connect(&mut ws_manager)
+            .await?
+            .add_subscription(identifier, sender_channel, recorder)
+            .await
+    }
+
+    async fn connect<'a>(
+        &self,
+        ws_manager: &'a mut Option<WsManager>,
+    ) -> Result<&'a mut WsManager> {
+        if ws_manager.is_none() {
+            *ws_manager = Some(
+                WsManager::new(
+                    format!("ws{}/ws", &self.http_client.base_url[4..]),
+                    self.reconnect,
+                    self.ping_interval,
+                )
+                .await?,
+            );
         }
+        ws_manager.as_mut().ok_or(Error::WsManagerNotFound)
+    }
 
-        let identifier = serde_json::to_string(&subscription)
-            .map_err(|e| Error::JsonParse(e.to_string()))
-            .unwrap();
-        self.ws_manager
+    // Sends an info or action request over the websocket instead of HTTP, e.g.
+    // `{"type": "info", "payload": {"type": "l2Book", "coin": "ETH"}}`, and waits for its response
+    pub async fn ws_post(&self, request: serde_json::Value) -> Result<serde_json::Value> {
+        let response = {
+            let mut ws_manager = self.ws_manager.lock().await;
+            self.connect(&mut ws_manager).await?.post(request).await?
+        };
+        response.await.map_err(|_| {
+            Error::WsPost("connection closed before the response was received".to_string())
+        })?
+    }
+
+    pub async fn unsubscribe(&self, subscription_id: u32) -> Result<()> {
+        let mut ws_manager_guard = self.ws_manager.lock().await;
+        let ws_manager = ws_manager_guard
             .as_mut()
-            .ok_or(Error::WsManagerNotFound)?
-            .add_subscription(identifier, sender_channel)
-            .await
+            .ok_or(Error::SubscriptionNotFound)?;
+        ws_manager.remove_subscription(subscription_id).await?;
+
+        // Dropping the manager stops its ping and reader tasks
+        if ws_manager.is_empty() {
+            *ws_manager_guard = None;
+        }
+        Ok(())
     }
 
-    pub async fn unsubscribe(&mut self, subscription_id: u32) -> Result<()> {
-        if self.ws_manager.is_none() {
-            let ws_manager = WsManager::new(
-                format!("ws{}/ws", &self.http_client.base_url[4..]),
-                self.reconnect,
-            )
-            .await?;
-            self.ws_manager = Some(ws_manager);
+    // Removed subscriptions are not renewed when the connection is re-established
+    pub async fn unsubscribe_all(&self) -> Result<()> {
+        let mut ws_manager_guard = self.ws_manager.lock().await;
+        let Some(ws_manager) = ws_manager_guard.as_mut() else {
+            return Ok(());
+        };
+        for (subscription_id, _) in ws_manager.active_subscriptions()? {
+            ws_manager.remove_subscription(subscription_id).await?;
         }
+        *ws_manager_guard = None;
+        Ok(())
+    }
 
+    pub async fn active_subscriptions(&self) -> Result<Vec<(u32, Subscription)>> {
         self.ws_manager
-            .as_mut()
-            .ok_or(Error::WsManagerNotFound)?
-            .remove_subscription(subscription_id)
+            .lock()
             .await
+            .as_ref()
+            .map_or(Ok(Vec::new()), WsManager::active_subscriptions)
     }
 
     async fn send_info_request<T: for<'a> Deserialize<'a>>(
@@ -167,8 +440,15 @@ impl InfoClient {
         info_request: InfoRequest,
     ) -> Result<T> {
         let data =
-            serde_json::to_string(&info_request).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let return_data = self.http_client.post("/info", data).await?;
+            serde_json::to_value(&info_request).map_err(|e| Error::JsonParse(e.to_string()))?;
+        let request_type = data["type"].as_str().unwrap_or_default().to_string();
+        let start = Instant::now();
+        let return_data = self.http_client.post("/info", data.to_string()).await;
+        debug!(
+            "POST /info request {request_type} took {:?}",
+            start.elapsed()
+        );
+        let return_data = return_data?;
         serde_json::from_str(&return_data).map_err(|e| Error::JsonParse(e.to_string()))
     }
 
@@ -177,13 +457,25 @@ impl InfoClient {
         self.send_info_request(input).await
     }
 
-    pub async fn frontend_open_orders(&self, address: Address) -> Result<Vec<FrontendOpenOrdersResponse>> {
+    pub async fn frontend_open_orders(
+        &self,
+        address: Address,
+    ) -> Result<Vec<FrontendOpenOrdersResponse>> {
         let input = InfoRequest::FrontendOpenOrders { user: address };
         self.send_info_request(input).await
     }
 
     pub async fn user_state(&self, address: Address) -> Result<UserStateResponse> {
-        let input = InfoRequest::UserState { user: address };
+        self.user_state_for_dex(address, None).await
+    }
+
+    // `dex` names a builder deployed perp dex, None is the default dex
+    pub async fn user_state_for_dex(
+        &self,
+        address: Address,
+        dex: Option<String>,
+    ) -> Result<UserStateResponse> {
+        let input = InfoRequest::UserState { user: address, dex };
         self.send_info_request(input).await
     }
 
@@ -197,13 +489,122 @@ impl InfoClient {
         self.send_info_request(input).await
     }
 
+    pub async fn account_overview(&self, address: Address) -> Result<AccountOverview> {
+        let (user_state, spot_balances) =
+            tokio::try_join!(self.user_state(address), self.user_token_balances(address))?;
+        let parse = |value: &str| value.parse::<f64>().map_err(|_| Error::FloatStringParse);
+        let (spot_usdc, spot_usdc_hold) = match spot_balances
+            .balances
+            .iter()
+            .find(|balance| balance.coin == "USDC")
+        {
+            Some(balance) => (parse(&balance.total)?, parse(&balance.hold)?),
+            None => (0., 0.),
+        };
+        let margin_summary = &user_state.margin_summary;
+        let perp_account_value = parse(&margin_summary.account_value)?;
+        Ok(AccountOverview {
+            perp_account_value,
+            perp_withdrawable: parse(&user_state.withdrawable)?,
+            total_margin_used: parse(&margin_summary.total_margin_used)?,
+            total_ntl_pos: parse(&margin_summary.total_ntl_pos)?,
+            spot_usdc,
+            spot_usdc_hold,
+            total_usdc: perp_account_value + spot_usdc,
+        })
+    }
+
+    pub async fn portfolio(&self, address: Address) -> Result<Portfolio> {
+        let input = InfoRequest::Portfolio { user: address };
+        self.send_info_request(input).await
+    }
+
+    pub async fn sub_accounts(&self, address: Address) -> Result<Vec<SubAccount>> {
+        let input = InfoRequest::SubAccounts { user: address };
+        // Users without sub-accounts get null instead of an empty list
+        let sub_accounts: Option<Vec<SubAccount>> = self.send_info_request(input).await?;
+        Ok(sub_accounts.unwrap_or_default())
+    }
+
+    // In tenths of a basis point, like `BuilderInfo::fee`
+    pub async fn max_builder_fee(&self, user: Address, builder: Address) -> Result<u64> {
+        let input = InfoRequest::MaxBuilderFee { user, builder };
+        self.send_info_request(input).await
+    }
+
+    pub async fn user_rate_limit(&self, address: Address) -> Result<UserRateLimitResponse> {
+        let input = InfoRequest::UserRateLimit { user: address };
+        self.send_info_request(input).await
+    }
+
     pub async fn user_fees(&self, address: Address) -> Result<UserFeesResponse> {
         let input = InfoRequest::UserFees { user: address };
         self.send_info_request(input).await
     }
 
     pub async fn meta(&self) -> Result<Meta> {
-        let input = InfoRequest::Meta;
+        self.meta_for_dex(None).await
+    }
+
+    pub async fn meta_for_dex(&self, dex: Option<String>) -> Result<Meta> {
+        let input = InfoRequest::Meta { dex };
+        self.send_info_request(input).await
+    }
+
+    pub async fn meta_and_asset_ctxs(&self) -> Result<(Meta, Vec<PerpsAssetCtx>)> {
+        let input = InfoRequest::MetaAndAssetCtxs;
+        self.send_info_request(input).await
+    }
+
+    // Contexts are returned in the order of `coins`; coins missing from the universe are skipped
+    pub async fn asset_ctxs_for(&self, coins: &[&str]) -> Result<Vec<(String, PerpsAssetCtx)>> {
+        let (meta, asset_ctxs) = self.meta_and_asset_ctxs().await?;
+        let mut asset_ctxs: HashMap<String, PerpsAssetCtx> = meta
+            .universe
+            .into_iter()
+            .map(|asset| asset.name)
+            .zip(asset_ctxs)
+            .filter(|(name, _)| coins.contains(&name.as_str()))
+            .collect();
+        Ok(coins
+            .iter()
+            .filter_map(|coin| asset_ctxs.remove_entry(*coin))
+            .collect())
+    }
+
+    pub async fn market_leaders(&self) -> Result<MarketLeaders> {
+        let (meta, asset_ctxs) = self.meta_and_asset_ctxs().await?;
+        let parse = |value: &str| value.parse::<f64>().map_err(|_| Error::FloatStringParse);
+        let by_volume = meta
+            .universe
+            .into_iter()
+            .zip(asset_ctxs)
+            .map(|(asset, asset_ctx)| {
+                let mark_px = parse(&asset_ctx.shared.mark_px)?;
+                let open_interest = parse(&asset_ctx.open_interest)?;
+                Ok(MarketStats {
+                    coin: asset.name,
+                    day_ntl_vlm: parse(&asset_ctx.shared.day_ntl_vlm)?,
+                    open_interest,
+                    open_interest_ntl: open_interest * mark_px,
+                    mark_px,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(MarketLeaders::new(by_volume))
+    }
+
+    // Per coin, the predicted rate on each venue, e.g. "HlPerp" and "BinPerp"
+    pub async fn predicted_fundings(
+        &self,
+    ) -> Result<Vec<(String, Vec<(String, PredictedFunding)>)>> {
+        let input = InfoRequest::PredictedFundings;
+        self.send_info_request(input).await
+    }
+
+    // Opening orders on these coins are rejected until open interest drops below the cap
+    pub async fn perps_at_open_interest_cap(&self) -> Result<Vec<String>> {
+        let input = InfoRequest::PerpsAtOpenInterestCap;
         self.send_info_request(input).await
     }
 
@@ -217,8 +618,44 @@ impl InfoClient {
         self.send_info_request(input).await
     }
 
+    pub async fn spot_meta_and_asset_ctxs(&self) -> Result<(SpotMeta, Vec<SpotAssetContext>)> {
+        let input = InfoRequest::SpotMetaAndAssetCtxs;
+        self.send_info_request(input).await
+    }
+
+    pub async fn spot_asset_ctxs_for(&self, coins: &[&str]) -> Result<Vec<SpotAssetContext>> {
+        let (_, asset_ctxs) = self.spot_meta_and_asset_ctxs().await?;
+        let mut asset_ctxs: HashMap<String, SpotAssetContext> = asset_ctxs
+            .into_iter()
+            .filter(|asset_ctx| coins.contains(&asset_ctx.coin.as_str()))
+            .map(|asset_ctx| (asset_ctx.coin.clone(), asset_ctx))
+            .collect();
+        Ok(coins
+            .iter()
+            .filter_map(|coin| asset_ctxs.remove(*coin))
+            .collect())
+    }
+
+    // `token_id` is the 16 byte hex id from `SpotMeta::tokens`, e.g. 0xc1fb593aeffbeb02f85e0308e9956a90
+    pub async fn token_details(&self, token_id: &str) -> Result<TokenDetailsResponse> {
+        let is_valid = token_id.len() == 34
+            && token_id.starts_with("0x")
+            && token_id[2..].chars().all(|c| c.is_ascii_hexdigit());
+        if !is_valid {
+            return Err(Error::InvalidTokenId(token_id.to_string()));
+        }
+        let input = InfoRequest::TokenDetails {
+            token_id: token_id.to_string(),
+        };
+        self.send_info_request(input).await
+    }
+
     pub async fn all_mids(&self) -> Result<HashMap<String, String>> {
-        let input = InfoRequest::AllMids;
+        Ok(self.all_mids_for_dex(None).await?.0)
+    }
+
+    pub async fn all_mids_for_dex(&self, dex: Option<String>) -> Result<AllMidsResponse> {
+        let input = InfoRequest::AllMids { dex };
         self.send_info_request(input).await
     }
 
@@ -227,18 +664,49 @@ impl InfoClient {
         self.send_info_request(input).await
     }
 
+    // Returns every fill in the range, requesting further pages while the exchange returns full ones
+    pub async fn user_fills_by_time(
+        &self,
+        address: Address,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> Result<Vec<UserFillsResponse>> {
+        paginate_by_time(
+            start_time,
+            Self::USER_FILLS_PAGE_SIZE,
+            |start_time| {
+                self.send_info_request(InfoRequest::UserFillsByTime {
+                    user: address,
+                    start_time,
+                    end_time,
+                })
+            },
+            |fill: &UserFillsResponse| fill.time,
+            |fill| fill.tid,
+        )
+        .await
+    }
+
     pub async fn funding_history(
         &self,
         coin: String,
         start_time: u64,
         end_time: Option<u64>,
     ) -> Result<Vec<FundingHistoryResponse>> {
-        let input = InfoRequest::FundingHistory {
-            coin,
+        paginate_by_time(
             start_time,
-            end_time,
-        };
-        self.send_info_request(input).await
+            Self::FUNDING_PAGE_SIZE,
+            |start_time| {
+                self.send_info_request(InfoRequest::FundingHistory {
+                    coin: coin.clone(),
+                    start_time,
+                    end_time,
+                })
+            },
+            |funding: &FundingHistoryResponse| funding.time,
+            |funding| funding.time,
+        )
+        .await
     }
 
     pub async fn user_funding_history(
@@ -247,12 +715,45 @@ impl InfoClient {
         start_time: u64,
         end_time: Option<u64>,
     ) -> Result<Vec<UserFundingResponse>> {
-        let input = InfoRequest::UserFunding {
-            user,
+        paginate_by_time(
             start_time,
-            end_time,
-        };
-        self.send_info_request(input).await
+            Self::FUNDING_PAGE_SIZE,
+            |start_time| {
+                self.send_info_request(InfoRequest::UserFunding {
+                    user,
+                    start_time,
+                    end_time,
+                })
+            },
+            |funding: &UserFundingResponse| funding.time,
+            // Funding payments all share the same hash
+            |funding| (funding.time, funding.delta.coin.clone()),
+        )
+        .await
+    }
+
+    // Deposits, withdrawals, transfers, liquidations and vault updates, i.e. every balance change
+    // other than funding payments
+    pub async fn user_non_funding_ledger_updates(
+        &self,
+        user: Address,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> Result<Vec<LedgerUpdateData>> {
+        paginate_by_time(
+            start_time,
+            Self::LEDGER_UPDATES_PAGE_SIZE,
+            |start_time| {
+                self.send_info_request(InfoRequest::UserNonFundingLedgerUpdates {
+                    user,
+                    start_time,
+                    end_time,
+                })
+            },
+            |update: &LedgerUpdateData| update.time,
+            |update| (update.time, update.hash.clone()),
+        )
+        .await
     }
 
     pub async fn recent_trades(&self, coin: String) -> Result<Vec<RecentTradesResponse>> {
@@ -261,7 +762,22 @@ impl InfoClient {
     }
 
     pub async fn l2_snapshot(&self, coin: String) -> Result<L2SnapshotResponse> {
-        let input = InfoRequest::L2Book { coin };
+        self.l2_snapshot_aggregated(coin, None, None).await
+    }
+
+    // `n_sig_figs` (2 to 5) merges levels to that many significant figures of price;
+    // `mantissa` (1, 2 or 5) is only allowed together with `n_sig_figs` 5
+    pub async fn l2_snapshot_aggregated(
+        &self,
+        coin: String,
+        n_sig_figs: Option<u32>,
+        mantissa: Option<u32>,
+    ) -> Result<L2SnapshotResponse> {
+        let input = InfoRequest::L2Book {
+            coin,
+            n_sig_figs,
+            mantissa,
+        };
         self.send_info_request(input).await
     }
 
@@ -272,6 +788,9 @@ impl InfoClient {
         start_time: u64,
         end_time: u64,
     ) -> Result<Vec<CandlesSnapshotResponse>> {
+        if !CANDLE_INTERVALS.contains(&interval.as_str()) {
+            return Err(Error::InvalidCandleInterval(interval));
+        }
         let input = InfoRequest::CandleSnapshot {
             req: CandleSnapshotRequest {
                 coin,
@@ -283,8 +802,46 @@ impl InfoClient {
         self.send_info_request(input).await
     }
 
-    pub async fn query_order_by_oid(&self, address: Address, oid: u64) -> Result<OrderStatusResponse> {
-        let input = InfoRequest::OrderStatus { user: address, oid };
+    pub async fn query_order_by_oid(
+        &self,
+        address: Address,
+        oid: u64,
+    ) -> Result<OrderStatusResponse> {
+        self.order_status(address, oid).await
+    }
+
+    pub async fn order_status(
+        &self,
+        address: Address,
+        oid: impl Into<OrderId>,
+    ) -> Result<OrderStatusResponse> {
+        let input = InfoRequest::OrderStatus {
+            user: address,
+            oid: oid.into(),
+        };
+        self.send_info_request(input).await
+    }
+
+    // With `user` set, `follower_state` describes that user's deposit in the vault
+    pub async fn vault_details(
+        &self,
+        vault_address: Address,
+        user: Option<Address>,
+    ) -> Result<VaultDetailsResponse> {
+        let input = InfoRequest::VaultDetails {
+            vault_address,
+            user,
+        };
+        self.send_info_request(input).await
+    }
+
+    pub async fn delegations(&self, address: Address) -> Result<Vec<DelegationResponse>> {
+        let input = InfoRequest::Delegations { user: address };
+        self.send_info_request(input).await
+    }
+
+    pub async fn delegator_summary(&self, address: Address) -> Result<DelegatorSummaryResponse> {
+        let input = InfoRequest::DelegatorSummary { user: address };
         self.send_info_request(input).await
     }
 
@@ -298,3 +855,236 @@ impl InfoClient {
         self.send_info_request(input).await
     }
 }
+
+// The next page starts at the latest timestamp seen so items sharing it are not lost; `key` drops the overlap
+async fn paginate_by_time<T, K, F, Fut>(
+    mut start_time: u64,
+    page_size: usize,
+    mut fetch_page: F,
+    time: impl Fn(&T) -> u64,
+    key: impl Fn(&T) -> K,
+) -> Result<Vec<T>>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+    K: Eq + Hash,
+{
+    let mut seen = HashSet::new();
+    let mut items = Vec::new();
+    loop {
+        let page = fetch_page(start_time).await?;
+        let is_full = page.len() >= page_size;
+        let latest_time = page.iter().map(&time).max();
+        let mut new_items = false;
+        for item in page {
+            if seen.insert(key(&item)) {
+                items.push(item);
+                new_items = true;
+            }
+        }
+        match latest_time {
+            // A full page within a single timestamp can only be skipped past
+            Some(latest_time) if is_full => {
+                start_time = if new_items {
+                    latest_time
+                } else {
+                    latest_time + 1
+                }
+            }
+            _ => return Ok(items),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LedgerUpdate;
+
+    #[tokio::test]
+    async fn paginate_by_time_keeps_items_sharing_a_page_boundary() -> Result<()> {
+        let times = [1, 2, 2, 3, 4, 4, 5];
+        let fetch_page = |start_time: u64| async move {
+            Ok(times
+                .iter()
+                .enumerate()
+                .filter(|(_, &time)| time >= start_time)
+                .take(3)
+                .map(|(id, &time)| (id, time))
+                .collect())
+        };
+        let items = paginate_by_time(1, 3, fetch_page, |&(_, time)| time, |&(id, _)| id).await?;
+        let ids: Vec<usize> = items.iter().map(|&(id, _)| id).collect();
+        assert_eq!(ids, vec![0, 1, 2, 3, 4, 5, 6]);
+        Ok(())
+    }
+
+    #[test]
+    fn dex_is_only_sent_when_given() {
+        let user: Address = "0x0bd592152216b0d21175c8cf856b5be05f3a7c3e"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(InfoRequest::UserState { user, dex: None }).unwrap(),
+            serde_json::json!({"type": "clearinghouseState", "user": "0x0bd592152216b0d21175c8cf856b5be05f3a7c3e"})
+        );
+        assert_eq!(
+            serde_json::to_value(InfoRequest::Meta {
+                dex: Some("test".to_string())
+            })
+            .unwrap(),
+            serde_json::json!({"type": "meta", "dex": "test"})
+        );
+        assert_eq!(
+            serde_json::to_value(InfoRequest::AllMids { dex: None }).unwrap(),
+            serde_json::json!({"type": "allMids"})
+        );
+    }
+
+    #[test]
+    fn all_mids_parse_to_floats() {
+        let all_mids: AllMidsResponse =
+            serde_json::from_str(r#"{"BTC":"65000.5","@107":"41.2","BAD":"n/a"}"#).unwrap();
+        assert_eq!(all_mids.get_f64("BTC"), Some(65000.5));
+        assert_eq!(all_mids.get_f64("@107"), Some(41.2));
+        assert_eq!(all_mids.get_f64("BAD"), None);
+        assert_eq!(all_mids.get_f64("ETH"), None);
+    }
+
+    #[test]
+    fn max_builder_fee_request_names_user_and_builder() {
+        let user: Address = "0x0bd592152216b0d21175c8cf856b5be05f3a7c3e"
+            .parse()
+            .unwrap();
+        let builder: Address = "0x1ab189b7801140900c711e458212f9c76f8dac79"
+            .parse()
+            .unwrap();
+        let request = serde_json::to_value(InfoRequest::MaxBuilderFee { user, builder }).unwrap();
+        assert_eq!(
+            request,
+            serde_json::json!({
+                "type": "maxBuilderFee",
+                "user": "0x0bd592152216b0d21175c8cf856b5be05f3a7c3e",
+                "builder": "0x1ab189b7801140900c711e458212f9c76f8dac79",
+            })
+        );
+    }
+
+    #[test]
+    fn sub_accounts_response_is_parsed() {
+        let sub_accounts: Option<Vec<SubAccount>> = serde_json::from_str(
+            r#"[{"name":"hedge","subAccountUser":"0x035605fc2f24d65300227189025e90a0d947f16c",
+                 "master":"0x0bd592152216b0d21175c8cf856b5be05f3a7c3e",
+                 "clearinghouseState":{"assetPositions":[],"withdrawable":"12.5",
+                   "marginSummary":{"accountValue":"12.5","totalMarginUsed":"0.0","totalNtlPos":"0.0","totalRawUsd":"12.5"},
+                   "crossMarginSummary":{"accountValue":"12.5","totalMarginUsed":"0.0","totalNtlPos":"0.0","totalRawUsd":"12.5"}},
+                 "spotState":{"balances":[]}}]"#,
+        )
+        .unwrap();
+        let sub_accounts = sub_accounts.unwrap();
+        assert_eq!(sub_accounts[0].name, "hedge");
+        assert_eq!(sub_accounts[0].clearinghouse_state.withdrawable, "12.5");
+        assert!(serde_json::from_str::<Option<Vec<SubAccount>>>("null")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn ledger_updates_response_is_parsed() {
+        let updates: Vec<LedgerUpdateData> = serde_json::from_str(
+            r#"[{"time":1681222254710,"hash":"0x1f6b","delta":{"type":"deposit","usdc":"2703997.45"}},
+                {"time":1681222254711,"hash":"0x2a7c","delta":{"type":"withdraw","usdc":"100.0","nonce":1681222254000,"fee":"1.0"}},
+                {"time":1681222254712,"hash":"0x3b8d","delta":{"type":"rewardsClaim","amount":"0.5"}}]"#,
+        )
+        .unwrap();
+        assert!(
+            matches!(&updates[0].delta, LedgerUpdate::Deposit(deposit) if deposit.usdc == "2703997.45")
+        );
+        assert!(
+            matches!(&updates[1].delta, LedgerUpdate::Withdraw(withdraw) if withdraw.fee == "1.0")
+        );
+        assert!(matches!(updates[2].delta, LedgerUpdate::Other));
+    }
+
+    #[test]
+    fn user_fees_response_is_parsed() {
+        let user_fees: UserFeesResponse = serde_json::from_str(
+            r#"{"dailyUserVlm":[{"date":"2025-05-23","userCross":"0.0","userAdd":"0.0","exchange":"2852367.0770729999"}],
+                "feeSchedule":{"cross":"0.00045","add":"0.00015","spotCross":"0.0007","spotAdd":"0.0004",
+                  "tiers":{"vip":[{"ntlCutoff":"5000000.0","cross":"0.0004","add":"0.00012"}],
+                    "mm":[{"makerFractionCutoff":"0.005","add":"-0.00001"}]},
+                  "referralDiscount":"0.04",
+                  "stakingDiscountTiers":[{"bpsOfMaxSupply":"0.0","discount":"0.0"},{"bpsOfMaxSupply":"0.0001","discount":"0.05"}]},
+                "userCrossRate":"0.000315","userAddRate":"0.000105","userSpotCrossRate":"0.00049",
+                "userSpotAddRate":"0.00028","activeReferralDiscount":"0.0",
+                "activeStakingDiscount":{"bpsOfMaxSupply":"0.0001","discount":"0.05"}}"#,
+        )
+        .unwrap();
+        assert_eq!(user_fees.user_cross_rate, "0.000315");
+        assert_eq!(user_fees.user_spot_add_rate.as_deref(), Some("0.00028"));
+        assert_eq!(user_fees.active_staking_discount.unwrap().discount, "0.05");
+        assert_eq!(user_fees.fee_schedule.tiers.vip[0].ntl_cutoff, "5000000.0");
+        assert_eq!(
+            user_fees.fee_schedule.staking_discount_tiers.unwrap().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn meta_and_asset_ctxs_response_is_parsed() {
+        let (meta, asset_ctxs): (Meta, Vec<PerpsAssetCtx>) = serde_json::from_str(
+            r#"[{"universe":[{"name":"BTC","szDecimals":5,"maxLeverage":50}]},
+                [{"dayNtlVlm":"1169046.29406","funding":"0.0000125","impactPxs":["14.3047","14.3444"],
+                  "markPx":"14.3161","midPx":"14.314","openInterest":"688.11","oraclePx":"14.32",
+                  "premium":"0.00031774","prevDayPx":"15.322"}]]"#,
+        )
+        .unwrap();
+        assert_eq!(meta.universe[0].name, "BTC");
+        assert_eq!(asset_ctxs[0].funding, "0.0000125");
+        assert_eq!(asset_ctxs[0].open_interest, "688.11");
+        assert_eq!(asset_ctxs[0].shared.mark_px, "14.3161");
+        assert_eq!(asset_ctxs[0].shared.mid_px.as_deref(), Some("14.314"));
+    }
+
+    #[test]
+    fn spot_meta_and_asset_ctxs_response_is_parsed() {
+        let (spot_meta, asset_ctxs): (SpotMeta, Vec<SpotAssetContext>) = serde_json::from_str(
+            r#"[{"universe":[{"tokens":[1,0],"name":"PURR/USDC","index":0,"isCanonical":true}],
+                 "tokens":[{"name":"USDC","szDecimals":8,"weiDecimals":8,"index":0,"tokenId":"0x6d1e7cde53ba9467b783cb7c530ce054","isCanonical":true},
+                           {"name":"PURR","szDecimals":0,"weiDecimals":5,"index":1,"tokenId":"0xc1fb593aeffbeb02f85e0308e9956a90","isCanonical":true}]},
+                [{"dayNtlVlm":"8906.0","markPx":"0.14","midPx":"0.209265","prevDayPx":"0.20432",
+                  "circulatingSupply":"851681534.05516005","coin":"PURR/USDC"}]]"#,
+        )
+        .unwrap();
+        assert_eq!(spot_meta.universe[0].name, "PURR/USDC");
+        assert_eq!(asset_ctxs[0].coin, "PURR/USDC");
+        assert_eq!(asset_ctxs[0].circulating_supply, "851681534.05516005");
+        assert_eq!(asset_ctxs[0].day_ntl_vlm, "8906.0");
+    }
+
+    #[test]
+    fn portfolio_buckets_are_named() {
+        let history = r#"{"accountValueHistory":[[1741886630493,"0.0"]],"pnlHistory":[[1741886630493,"0.0"]],"vlm":"0.0"}"#;
+        let response = format!(
+            r#"[["day",{history}],["week",{history}],["month",{history}],["allTime",{history}],["perpDay",{history}]]"#
+        );
+        let portfolio: Portfolio = serde_json::from_str(&response).unwrap();
+        assert_eq!(portfolio.all_time.account_value_history[0].0, 1741886630493);
+        assert!(portfolio.perp_day.is_some());
+        assert!(portfolio.perp_all_time.is_none());
+        assert!(serde_json::from_str::<Portfolio>(&format!(r#"[["day",{history}]]"#)).is_err());
+    }
+
+    #[test]
+    fn historical_orders_without_tif_are_parsed() {
+        let orders: Vec<OrderInfo> = serde_json::from_str(
+            r#"[{"order":{"coin":"ETH","side":"A","limitPx":"2412.7","sz":"0.0","oid":1,"timestamp":1724361546645,
+                "triggerCondition":"N/A","isTrigger":false,"triggerPx":"0.0","children":[],"isPositionTpsl":false,
+                "reduceOnly":true,"orderType":"Market","origSz":"0.0076","tif":null,"cloid":null},
+                "status":"filled","statusTimestamp":1724361546645}]"#,
+        )
+        .unwrap();
+        assert_eq!(orders[0].status, "filled");
+        assert!(orders[0].order.tif.is_none());
+    }
+}
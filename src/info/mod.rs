@@ -1,3 +1,4 @@
+mod candles;
 pub(super) mod info_client;
 mod response_structs;
 mod sub_structs;
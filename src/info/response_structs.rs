@@ -1,10 +1,13 @@
 use crate::{
     info::{AssetPosition, Level, MarginSummary},
-    DailyUserVlm, Delta, FeeSchedule, OrderInfo, Referrer, ReferrerState, UserTokenBalance,
+    DailyUserVlm, Delta, FeeSchedule, MarketStats, OrderInfo, PortfolioHistory, Referrer,
+    ReferrerState, StakingDiscount, UserTokenBalance, VaultFollower,
 };
+use alloy::primitives::Address;
 use serde::Deserialize;
+use std::collections::HashMap;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UserStateResponse {
     pub asset_positions: Vec<AssetPosition>,
@@ -13,6 +16,15 @@ pub struct UserStateResponse {
     pub withdrawable: String,
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccount {
+    pub name: String,
+    pub sub_account_user: Address,
+    pub master: Address,
+    pub clearinghouse_state: UserStateResponse,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct UserTokenBalanceResponse {
     pub balances: Vec<UserTokenBalance>,
@@ -26,9 +38,12 @@ pub struct UserFeesResponse {
     pub fee_schedule: FeeSchedule,
     pub user_add_rate: String,
     pub user_cross_rate: String,
+    pub user_spot_add_rate: Option<String>,
+    pub user_spot_cross_rate: Option<String>,
+    pub active_staking_discount: Option<StakingDiscount>,
 }
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenOrdersResponse {
     pub coin: String,
@@ -38,9 +53,11 @@ pub struct OpenOrdersResponse {
     pub sz: String,
     pub timestamp: u64,
     pub reduce_only: Option<bool>,
+    /// Size when the order was placed, `sz` is what is still resting
+    pub orig_sz: Option<String>,
+    pub cloid: Option<String>,
 }
 
-
 #[derive(serde::Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct FrontendOpenOrdersResponse {
@@ -57,6 +74,8 @@ pub struct FrontendOpenOrdersResponse {
     pub timestamp: u64,
     pub trigger_condition: String,
     pub trigger_px: String,
+    pub tif: Option<String>,
+    pub cloid: Option<String>,
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -74,6 +93,7 @@ pub struct UserFillsResponse {
     pub sz: String,
     pub time: u64,
     pub fee: String,
+    pub tid: u64,
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -100,6 +120,32 @@ pub struct L2SnapshotResponse {
     pub time: u64,
 }
 
+impl L2SnapshotResponse {
+    pub fn bids(&self) -> &[Level] {
+        self.levels.first().map_or(&[], Vec::as_slice)
+    }
+
+    pub fn asks(&self) -> &[Level] {
+        self.levels.get(1).map_or(&[], Vec::as_slice)
+    }
+}
+
+// Mid prices keyed by coin, spot pairs other than PURR/USDC use their "@<index>" name
+#[derive(Deserialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct AllMidsResponse(pub HashMap<String, String>);
+
+impl AllMidsResponse {
+    pub fn get(&self, coin: &str) -> Option<&str> {
+        self.0.get(coin).map(String::as_str)
+    }
+
+    // None if the coin has no mid or its mid is not a number
+    pub fn get_f64(&self, coin: &str) -> Option<f64> {
+        self.get(coin)?.parse().ok()
+    }
+}
+
 #[derive(serde::Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct RecentTradesResponse {
@@ -152,3 +198,146 @@ pub struct ReferralResponse {
     pub claimed_rewards: String,
     pub referrer_state: ReferrerState,
 }
+
+#[derive(Debug, Clone)]
+pub struct MarketLeaders {
+    /// Sorted by 24h notional volume, descending
+    pub by_volume: Vec<MarketStats>,
+    /// Sorted by open interest notional, descending
+    pub by_open_interest: Vec<MarketStats>,
+}
+
+impl MarketLeaders {
+    pub(crate) fn new(mut by_volume: Vec<MarketStats>) -> MarketLeaders {
+        let mut by_open_interest = by_volume.clone();
+        by_volume.sort_by(|a, b| b.day_ntl_vlm.total_cmp(&a.day_ntl_vlm));
+        by_open_interest.sort_by(|a, b| b.open_interest_ntl.total_cmp(&a.open_interest_ntl));
+        MarketLeaders {
+            by_volume,
+            by_open_interest,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountOverview {
+    /// Perp account value including unrealized pnl
+    pub perp_account_value: f64,
+    pub perp_withdrawable: f64,
+    pub total_margin_used: f64,
+    /// Notional of all open perp positions
+    pub total_ntl_pos: f64,
+    pub spot_usdc: f64,
+    /// Spot USDC reserved by open spot orders
+    pub spot_usdc_hold: f64,
+    /// Perp account value plus spot USDC
+    pub total_usdc: f64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenDetailsResponse {
+    pub name: String,
+    pub max_supply: String,
+    pub total_supply: String,
+    pub circulating_supply: String,
+    pub sz_decimals: u8,
+    pub wei_decimals: u8,
+    pub mid_px: Option<String>,
+    pub mark_px: Option<String>,
+    pub prev_day_px: Option<String>,
+    pub deployer: Option<String>,
+    pub deploy_gas: Option<String>,
+    pub deploy_time: Option<String>,
+    pub seeded_usdc: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationResponse {
+    pub validator: Address,
+    pub amount: String,
+    pub locked_until_timestamp: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegatorSummaryResponse {
+    pub delegated: String,
+    pub undelegated: String,
+    pub total_pending_withdrawal: String,
+    pub n_pending_withdrawals: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultDetailsResponse {
+    pub name: String,
+    pub vault_address: Address,
+    pub leader: Address,
+    pub description: String,
+    /// Keyed by period, e.g. "day", "week", "month" and "allTime"
+    pub portfolio: Vec<(String, PortfolioHistory)>,
+    pub apr: f64,
+    pub follower_state: Option<VaultFollower>,
+    pub leader_fraction: f64,
+    pub leader_commission: f64,
+    pub followers: Vec<VaultFollower>,
+    pub max_distributable: f64,
+    pub max_withdrawable: f64,
+    pub is_closed: bool,
+    pub allow_deposits: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UserRateLimitResponse {
+    pub cum_vlm: String,
+    pub n_requests_used: u64,
+    /// Grows with traded volume
+    pub n_requests_cap: u64,
+}
+
+// The exchange returns the buckets as (name, history) pairs
+#[derive(Deserialize, Debug)]
+#[serde(try_from = "Vec<(String, PortfolioHistory)>")]
+pub struct Portfolio {
+    pub day: PortfolioHistory,
+    pub week: PortfolioHistory,
+    pub month: PortfolioHistory,
+    pub all_time: PortfolioHistory,
+    /// The same buckets restricted to the perp account
+    pub perp_day: Option<PortfolioHistory>,
+    pub perp_week: Option<PortfolioHistory>,
+    pub perp_month: Option<PortfolioHistory>,
+    pub perp_all_time: Option<PortfolioHistory>,
+}
+
+impl TryFrom<Vec<(String, PortfolioHistory)>> for Portfolio {
+    type Error = String;
+
+    fn try_from(buckets: Vec<(String, PortfolioHistory)>) -> Result<Portfolio, String> {
+        let mut buckets: HashMap<String, PortfolioHistory> = buckets.into_iter().collect();
+        let mut required = |name: &str| {
+            buckets
+                .remove(name)
+                .ok_or(format!("missing portfolio bucket {name}"))
+        };
+        let (day, week, month, all_time) = (
+            required("day")?,
+            required("week")?,
+            required("month")?,
+            required("allTime")?,
+        );
+        Ok(Portfolio {
+            day,
+            week,
+            month,
+            all_time,
+            perp_day: buckets.remove("perpDay"),
+            perp_week: buckets.remove("perpWeek"),
+            perp_month: buckets.remove("perpMonth"),
+            perp_all_time: buckets.remove("perpAllTime"),
+        })
+    }
+}
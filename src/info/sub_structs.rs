@@ -41,7 +41,7 @@ pub struct AssetPosition {
     pub type_string: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MarginSummary {
     pub account_value: String,
@@ -83,8 +83,19 @@ pub struct DailyUserVlm {
 pub struct FeeSchedule {
     pub add: String,
     pub cross: String,
+    pub spot_add: Option<String>,
+    pub spot_cross: Option<String>,
     pub referral_discount: String,
     pub tiers: Tiers,
+    pub staking_discount_tiers: Option<Vec<StakingDiscount>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StakingDiscount {
+    /// Share of the HYPE max supply staked by the user needed for the discount
+    pub bps_of_max_supply: String,
+    pub discount: String,
 }
 
 #[derive(Deserialize, Debug)]
@@ -109,10 +120,15 @@ pub struct Vip {
 }
 
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct UserTokenBalance {
     pub coin: String,
+    /// Index of the token in `SpotMeta::tokens`
+    pub token: u32,
     pub hold: String,
     pub total: String,
+    /// Notional value at the average entry price
+    pub entry_ntl: String,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -139,7 +155,8 @@ pub struct BasicOrderInfo {
     pub reduce_only: bool,
     pub order_type: String,
     pub orig_sz: String,
-    pub tif: String,
+    /// `None` for market and trigger orders
+    pub tif: Option<String>,
     pub cloid: Option<String>,
 }
 
@@ -162,3 +179,44 @@ pub struct ReferrerState {
 pub struct ReferrerData {
     pub required: String,
 }
+
+#[derive(Debug, Clone)]
+pub struct MarketStats {
+    pub coin: String,
+    pub day_ntl_vlm: f64,
+    pub open_interest: f64,
+    /// Open interest valued at the mark price
+    pub open_interest_ntl: f64,
+    pub mark_px: f64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioHistory {
+    /// (timestamp ms, value) pairs
+    pub account_value_history: Vec<(u64, String)>,
+    pub pnl_history: Vec<(u64, String)>,
+    pub vlm: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultFollower {
+    /// The follower's address, or "Leader" for the vault leader
+    pub user: String,
+    pub vault_equity: String,
+    pub pnl: String,
+    pub all_time_pnl: String,
+    pub days_following: u64,
+    pub vault_entry_time: u64,
+    /// Deposits cannot be withdrawn before this time
+    pub lockup_until: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PredictedFunding {
+    pub funding_rate: String,
+    pub next_funding_time: u64,
+    pub funding_interval_hours: Option<u64>,
+}
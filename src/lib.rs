@@ -11,11 +11,18 @@ mod proxy_digest;
 mod req;
 mod signature;
 mod ws;
-pub use consts::{EPSILON, LOCAL_API_URL, MAINNET_API_URL, TESTNET_API_URL};
+pub use consts::{CANDLE_INTERVALS, EPSILON, LOCAL_API_URL, MAINNET_API_URL, TESTNET_API_URL};
 pub use errors::Error;
 pub use exchange::*;
-pub use helpers::{bps_diff, truncate_float, BaseUrl};
+pub use helpers::{
+    bps_diff, cloid_from_hex, new_cloid, round_to_decimals, round_to_significant_and_decimal,
+    round_to_significant_and_decimal_directed, truncate_float, uuid_to_hex_string, Amount, BaseUrl,
+};
 pub use info::{info_client::*, *};
 pub use market_maker::{MarketMaker, MarketMakerInput, MarketMakerRestingOrder};
-pub use meta::{AssetMeta, Meta};
+pub use meta::{
+    AssetMeta, Meta, SpotAssetContext, SpotAssetMeta, SpotMeta, SpotMetaAndAssetCtxs, TokenInfo,
+};
+pub use req::{RateLimit, RateLimitHeaders, RetryPolicy};
+pub use signature::{recover_l1_action, verify_agent};
 pub use ws::*;
@@ -5,8 +5,8 @@ use tokio::sync::mpsc::unbounded_channel;
 
 use crate::{
     bps_diff, truncate_float, BaseUrl, ClientCancelRequest, ClientLimit, ClientOrder,
-    ClientOrderRequest, ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, InfoClient,
-    Message, Subscription, UserData, EPSILON,
+    ClientOrderRequest, ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, Grouping,
+    InfoClient, Message, Subscription, Tif, UserData, EPSILON,
 };
 #[derive(Debug)]
 pub struct MarketMakerRestingOrder {
@@ -193,17 +193,15 @@ impl MarketMaker {
             .order(
                 ClientOrderRequest {
                     asset,
-                    is_buy,
+                    side: is_buy.into(),
                     reduce_only: false,
                     limit_px: price,
                     sz: amount,
                     cloid: None,
-                    order_type: ClientOrder::Limit(ClientLimit {
-                        tif: "Gtc".to_string(),
-                    }),
+                    order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
                 },
                 None,
-                "na".to_string(),
+                Grouping::Na,
             )
             .await;
         match order {
@@ -43,6 +43,19 @@ impl SpotMeta {
 
         coin_to_asset
     }
+
+    // `asset` is the spot asset index minus 10000; a pair takes its size decimals from its base token
+    pub fn pair(&self, asset: u32) -> Option<(&SpotAssetMeta, &TokenInfo)> {
+        let pair = self
+            .universe
+            .iter()
+            .find(|pair| pair.index == asset as usize)?;
+        let base_token = self
+            .tokens
+            .iter()
+            .find(|token| token.index == pair.tokens[0])?;
+        Some((pair, base_token))
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -95,5 +108,31 @@ pub struct TokenInfo {
 #[derive(Debug, Deserialize, Clone)]
 pub struct EvmContract {
     pub address: Address,
-    pub evm_extra_wei_decimals: i16
+    pub evm_extra_wei_decimals: i16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spot_pairs_resolve_to_offset_asset_indices() {
+        let spot_meta: SpotMeta = serde_json::from_str(
+            r#"{"universe":[{"tokens":[1,0],"name":"PURR/USDC","index":0,"isCanonical":true},
+                            {"tokens":[2,0],"name":"@1","index":1,"isCanonical":false}],
+                "tokens":[{"name":"USDC","szDecimals":8,"weiDecimals":8,"index":0,"tokenId":"0x6d1e7cde53ba9467b783cb7c530ce054","isCanonical":true},
+                          {"name":"PURR","szDecimals":0,"weiDecimals":5,"index":1,"tokenId":"0xc1fb593aeffbeb02f85e0308e9956a90","isCanonical":true},
+                          {"name":"HFUN","szDecimals":2,"weiDecimals":8,"index":2,"tokenId":"0xbaf265ef389da684513d98d68edf4eae","isCanonical":false}]}"#,
+        )
+        .unwrap();
+        let coin_to_asset = spot_meta.add_pair_and_name_to_index_map(HashMap::new());
+        assert_eq!(coin_to_asset["PURR/USDC"], 10000);
+        assert_eq!(coin_to_asset["HFUN/USDC"], 10001);
+        assert_eq!(coin_to_asset["@1"], 10001);
+
+        let (pair, base_token) = spot_meta.pair(coin_to_asset["HFUN/USDC"] - 10000).unwrap();
+        assert_eq!(pair.name, "@1");
+        assert_eq!(base_token.sz_decimals, 2);
+        assert!(spot_meta.pair(2).is_none());
+    }
 }
@@ -1,6 +1,12 @@
 use crate::{prelude::*, BaseUrl, Error};
-use reqwest::{Client, Response};
+use log::{trace, warn};
+use reqwest::{header::HeaderMap, Client, Response};
 use serde::Deserialize;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::time::sleep;
 
 #[derive(Deserialize, Debug)]
 struct ErrorData {
@@ -9,10 +15,116 @@ struct ErrorData {
     msg: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct HttpClient {
     pub client: Client,
     pub base_url: String,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) retry_policy: RetryPolicy,
+    last_rate_limit: Arc<Mutex<Option<RateLimitHeaders>>>,
+    // Shared by clones, so clients built from one another draw from the same bucket
+    rate_limiter: Option<Arc<Mutex<TokenBucket>>>,
+}
+
+/// Retries for failed requests to the `/info` endpoint, i.e. every `InfoClient` query.
+/// Requests to `/exchange` are never retried: the action may already have been accepted,
+/// and resending it with a fresh nonce could place it twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 0,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+}
+
+/// Client side throttling of outgoing requests: up to `burst` requests are sent at once,
+/// after which requests are queued and sent at `requests_per_second`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub requests_per_second: u32,
+    pub burst: u32,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate_limit: RateLimit, now: Instant) -> TokenBucket {
+        let burst = rate_limit.burst.max(1) as f64;
+        TokenBucket {
+            rate: rate_limit.requests_per_second.max(1) as f64,
+            burst,
+            tokens: burst,
+            last_refill: now,
+        }
+    }
+
+    // Takes a token and returns how long to wait before using it. Tokens may go negative so
+    // that waiting requests hold their place in the queue without keeping the bucket locked
+    fn reserve(&mut self, now: Instant) -> Duration {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.last_refill = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+// Network failures, timeouts, server errors and rate limiting may succeed on a later attempt
+fn is_retryable(err: &Error) -> bool {
+    match err {
+        Error::GenericRequest(_) | Error::ServerRequest { .. } => true,
+        Error::ClientRequest { status_code, .. } => *status_code == 429,
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitHeaders {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// Seconds until the limit resets
+    pub reset: Option<u64>,
+}
+
+impl RateLimitHeaders {
+    fn from_headers(headers: &HeaderMap) -> Option<RateLimitHeaders> {
+        let header = |name: &str| headers.get(name)?.to_str().ok()?.parse().ok();
+        let rate_limit = RateLimitHeaders {
+            limit: header("x-ratelimit-limit"),
+            remaining: header("x-ratelimit-remaining"),
+            reset: header("x-ratelimit-reset"),
+        };
+        (rate_limit != RateLimitHeaders::default()).then_some(rate_limit)
+    }
 }
 
 async fn parse_response(response: Response) -> Result<String> {
@@ -50,13 +162,88 @@ async fn parse_response(response: Response) -> Result<String> {
 }
 
 impl HttpClient {
+    pub fn new(client: Client, base_url: String) -> HttpClient {
+        HttpClient {
+            client,
+            base_url,
+            timeout: None,
+            retry_policy: RetryPolicy::default(),
+            last_rate_limit: Arc::new(Mutex::new(None)),
+            rate_limiter: None,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> HttpClient {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> HttpClient {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> HttpClient {
+        let bucket = TokenBucket::new(rate_limit, Instant::now());
+        self.rate_limiter = Some(Arc::new(Mutex::new(bucket)));
+        self
+    }
+
+    // Copies the timeout, retry policy and rate limiter of another client
+    pub(crate) fn with_settings_of(mut self, other: &HttpClient) -> HttpClient {
+        self.timeout = other.timeout;
+        self.retry_policy = other.retry_policy;
+        self.rate_limiter = other.rate_limiter.clone();
+        self
+    }
+
+    async fn throttle(&self) {
+        let Some(rate_limiter) = &self.rate_limiter else {
+            return;
+        };
+        let wait = match rate_limiter.lock() {
+            Ok(mut bucket) => bucket.reserve(Instant::now()),
+            Err(_) => return,
+        };
+        if !wait.is_zero() {
+            trace!("Rate limited, waiting {wait:?}");
+            sleep(wait).await;
+        }
+    }
+
     pub async fn post(&self, url_path: &'static str, data: String) -> Result<String> {
+        let max_retries = if url_path == "/info" {
+            self.retry_policy.max_retries
+        } else {
+            0
+        };
+        let mut attempt = 0;
+        loop {
+            match self.send(url_path, data.clone()).await {
+                Err(err) if attempt < max_retries && is_retryable(&err) => {
+                    let backoff = self.retry_policy.backoff(attempt);
+                    warn!("Request to {url_path} failed, retrying in {backoff:?}: {err}");
+                    sleep(backoff).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send(&self, url_path: &'static str, data: String) -> Result<String> {
+        self.throttle().await;
+        trace!("POST {url_path} payload {data}");
         let full_url = format!("{}{url_path}", self.base_url);
-        let request = self
+        let mut request = self
             .client
             .post(full_url)
             .header("Content-Type", "application/json")
-            .body(data)
+            .body(data);
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+        let request = request
             .build()
             .map_err(|e| Error::GenericRequest(e.to_string()))?;
         let result = self
@@ -64,10 +251,102 @@ impl HttpClient {
             .execute(request)
             .await
             .map_err(|e| Error::GenericRequest(e.to_string()))?;
-        parse_response(result).await
+        if let Some(rate_limit) = RateLimitHeaders::from_headers(result.headers()) {
+            if let Ok(mut last_rate_limit) = self.last_rate_limit.lock() {
+                *last_rate_limit = Some(rate_limit);
+            }
+        }
+        let response = parse_response(result).await;
+        trace!("POST {url_path} response {response:?}");
+        response
+    }
+
+    // `None` until a response carried rate limit headers
+    pub fn last_rate_limit(&self) -> Option<RateLimitHeaders> {
+        *self.last_rate_limit.lock().ok()?
     }
 
     pub fn is_mainnet(&self) -> bool {
         self.base_url == BaseUrl::Mainnet.get_url()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn retry_backoff_doubles_up_to_max() {
+        let retry_policy = RetryPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(1),
+        };
+        let backoffs: Vec<_> = (0..4)
+            .map(|attempt| retry_policy.backoff(attempt))
+            .collect();
+        assert_eq!(
+            backoffs,
+            vec![
+                Duration::from_millis(250),
+                Duration::from_millis(500),
+                Duration::from_secs(1),
+                Duration::from_secs(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn token_bucket_allows_burst_then_spaces_requests() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(
+            RateLimit {
+                requests_per_second: 10,
+                burst: 2,
+            },
+            start,
+        );
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::from_millis(100));
+        assert_eq!(bucket.reserve(start), Duration::from_millis(200));
+
+        let later = start + Duration::from_secs(1);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn gzip_responses_are_decompressed() -> Result<()> {
+        // `{"ETH":"1800.5"}` gzipped
+        const BODY: [u8; 36] = [
+            31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 171, 86, 114, 13, 241, 80, 178, 82, 50, 180, 48, 48,
+            208, 51, 85, 170, 5, 0, 99, 100, 222, 226, 16, 0, 0, 0,
+        ];
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let len = stream.read(&mut request).await.unwrap();
+            let request = String::from_utf8_lossy(&request[..len]).to_lowercase();
+            let header = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-encoding: gzip\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                BODY.len()
+            );
+            stream.write_all(header.as_bytes()).await.unwrap();
+            stream.write_all(&BODY).await.unwrap();
+            request
+        });
+
+        let http_client = HttpClient::new(Client::new(), base_url);
+        let response = http_client.post("/info", "{}".to_string()).await?;
+        let mids: std::collections::HashMap<String, String> =
+            serde_json::from_str(&response).unwrap();
+        assert_eq!(mids["ETH"], "1800.5");
+        assert!(server.await.unwrap().contains("accept-encoding: gzip"));
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use alloy::{
-    primitives::B256,
+    primitives::{Address, B256},
     signers::{Signature, Signer},
 };
 use ethers::{
@@ -7,22 +7,48 @@ use ethers::{
     types::{transaction::eip712::Eip712, H256},
 };
 
-use crate::{prelude::*, proxy_digest::Sha256Proxy, signature::agent::l1, Error};
+use crate::{prelude::*, proxy_digest::Sha256Proxy, signature::agent::l1, ApproveAgent, Error};
+
+fn l1_agent(connection_id: B256, is_mainnet: bool) -> l1::Agent {
+    let source = if is_mainnet { "a" } else { "b" }.to_string();
+    l1::Agent {
+        source,
+        connection_id: H256(connection_id.0),
+    }
+}
 
 pub(crate) async fn sign_l1_action<S: Signer>(
     wallet: &S,
     connection_id: B256,
     is_mainnet: bool,
 ) -> Result<Signature> {
-    let source = if is_mainnet { "a" } else { "b" }.to_string();
-    sign_typed_data(
-        &l1::Agent {
-            source,
-            connection_id: H256(connection_id.0),
-        },
-        wallet,
-    )
-    .await
+    sign_typed_data(&l1_agent(connection_id, is_mainnet), wallet).await
+}
+
+pub fn recover_l1_action(
+    connection_id: B256,
+    signature: &Signature,
+    is_mainnet: bool,
+) -> Result<Address> {
+    recover_typed_data(&l1_agent(connection_id, is_mainnet), signature)
+}
+
+// Checks that the agent approval was signed by `user`, the account the agent trades for
+pub fn verify_agent(
+    approve_agent: &ApproveAgent,
+    signature: &Signature,
+    user: Address,
+) -> Result<bool> {
+    Ok(recover_typed_data(approve_agent, signature)? == user)
+}
+
+fn recover_typed_data<T: Eip712>(payload: &T, signature: &Signature) -> Result<Address> {
+    let encoded = payload
+        .encode_eip712()
+        .map_err(|e| Error::Eip712(e.to_string()))?;
+    signature
+        .recover_address_from_prehash(&B256::from(encoded))
+        .map_err(|e| Error::SignatureFailure(e.to_string()))
 }
 
 pub(crate) async fn sign_typed_data<T: Eip712, S: Signer>(
@@ -40,6 +66,50 @@ async fn sign_hash<S: Signer>(hash: H256, signer: &S) -> Result<Signature> {
     let signature = signer
         .sign_hash(&B256::from_slice(&message.finalize()))
         .await
-        .unwrap();
+        .map_err(|e| Error::SignatureFailure(e.to_string()))?;
     Ok(signature)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{primitives::U256, signers::local::PrivateKeySigner};
+
+    fn get_wallet() -> Result<PrivateKeySigner> {
+        let priv_key = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e";
+        priv_key
+            .parse::<PrivateKeySigner>()
+            .map_err(|e| Error::Wallet(e.to_string()))
+    }
+
+    #[tokio::test]
+    async fn signed_actions_recover_to_the_signer() -> Result<()> {
+        let wallet = get_wallet()?;
+        let connection_id = B256::repeat_byte(0xde);
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(
+            recover_l1_action(connection_id, &signature, true)?,
+            wallet.address()
+        );
+        assert_ne!(
+            recover_l1_action(connection_id, &signature, false)?,
+            wallet.address()
+        );
+
+        let approve_agent = ApproveAgent {
+            signature_chain_id: U256::from(421614),
+            hyperliquid_chain: "Testnet".to_string(),
+            agent_address: Address::repeat_byte(0x11),
+            agent_name: None,
+            nonce: 1583838,
+        };
+        let signature = sign_typed_data(&approve_agent, &wallet).await?;
+        assert!(verify_agent(&approve_agent, &signature, wallet.address())?);
+        assert!(!verify_agent(
+            &approve_agent,
+            &signature,
+            Address::repeat_byte(0x11)
+        )?);
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 pub(crate) mod agent;
 mod create_signature;
 
+pub use create_signature::{recover_l1_action, verify_agent};
 pub(crate) use create_signature::{sign_l1_action, sign_typed_data};
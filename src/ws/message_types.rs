@@ -11,6 +11,11 @@ pub struct L2Book {
     pub data: L2BookData,
 }
 
+#[derive(Deserialize, Clone, Debug)]
+pub struct Bbo {
+    pub data: BboData,
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct AllMids {
     pub data: AllMidsData,
@@ -65,3 +70,13 @@ pub struct ActiveAssetCtx {
 pub struct ActiveSpotAssetCtx {
     pub data: ActiveSpotAssetCtxData,
 }
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ActiveAssetData {
+    pub data: ActiveAssetDataData,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Post {
+    pub data: PostData,
+}
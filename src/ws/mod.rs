@@ -1,7 +1,9 @@
 mod message_types;
+mod recording;
 mod sub_structs;
 mod ws_manager;
 pub use message_types::*;
+pub(crate) use recording::{replay, Recorder};
 pub use sub_structs::*;
-pub(crate) use ws_manager::WsManager;
 pub use ws_manager::{Message, Subscription};
+pub(crate) use ws_manager::{MessageSender, WsManager};
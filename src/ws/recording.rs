@@ -0,0 +1,105 @@
+use crate::{helpers::now_timestamp_ms, prelude::*, Error, Message};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, io::Write, path::Path, time::Duration};
+use tokio::{sync::mpsc::UnboundedSender, time};
+
+#[derive(Serialize, Deserialize)]
+struct RecordedFrame {
+    time: u64,
+    message: String,
+}
+
+// Appends every raw frame delivered to a subscription as one JSON line
+#[derive(Debug)]
+pub(crate) struct Recorder {
+    file: File,
+}
+
+impl Recorder {
+    pub(crate) fn create(path: &Path) -> Result<Recorder> {
+        let file = File::create(path).map_err(|e| Error::Recording(e.to_string()))?;
+        Ok(Recorder { file })
+    }
+
+    pub(crate) fn record(&mut self, raw: &str) -> Result<()> {
+        let mut line = serde_json::to_string(&RecordedFrame {
+            time: now_timestamp_ms(),
+            message: raw.to_string(),
+        })
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        line.push('\n');
+        self.file
+            .write_all(line.as_bytes())
+            .map_err(|e| Error::Recording(e.to_string()))
+    }
+}
+
+pub(crate) async fn replay(
+    path: &Path,
+    sender: &UnboundedSender<Message>,
+    speed: f64,
+) -> Result<()> {
+    // Also rejects NaN, an infinite speed replays without delay
+    if speed.is_nan() || speed <= 0. {
+        return Err(Error::InvalidReplaySpeed(speed));
+    }
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| Error::Recording(e.to_string()))?;
+    let mut last_time = None;
+    for line in contents.lines().filter(|line| !line.is_empty()) {
+        let frame = serde_json::from_str::<RecordedFrame>(line)
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        if let Some(last_time) = last_time {
+            let delay_ms = frame.time.saturating_sub(last_time) as f64 / speed;
+            if delay_ms > 0. {
+                let delay = Duration::try_from_secs_f64(delay_ms / 1000.).unwrap_or(Duration::MAX);
+                time::sleep(delay).await;
+            }
+        }
+        last_time = Some(frame.time);
+        let message = serde_json::from_str::<Message>(&frame.message)
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        sender
+            .send(message)
+            .map_err(|e| Error::WsSend(e.to_string()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    #[tokio::test]
+    async fn recorded_frames_replay_in_order() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("hl-recording-{}.jsonl", now_timestamp_ms()));
+        let mut recorder = Recorder::create(&path)?;
+        recorder.record(r#"{"channel":"allMids","data":{"mids":{"BTC":"65000.5"}}}"#)?;
+        recorder.record(r#"{"channel":"allMids","data":{"mids":{"BTC":"65001"}}}"#)?;
+        drop(recorder);
+
+        let (sender, mut receiver) = unbounded_channel();
+        replay(&path, &sender, f64::INFINITY).await?;
+        std::fs::remove_file(&path).map_err(|e| Error::Recording(e.to_string()))?;
+
+        let mut mids = Vec::new();
+        while let Ok(Message::AllMids(all_mids)) = receiver.try_recv() {
+            mids.push(all_mids.data.mids["BTC"].clone());
+        }
+        assert_eq!(mids, vec!["65000.5", "65001"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn replay_rejects_non_positive_speeds() {
+        let (sender, _receiver) = unbounded_channel();
+        for speed in [0., -1., f64::NAN] {
+            assert!(matches!(
+                replay(Path::new("missing.jsonl"), &sender, speed).await,
+                Err(Error::InvalidReplaySpeed(_))
+            ));
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::{Leverage, Meta, OpenOrdersResponse, UserStateResponse};
 use alloy::primitives::Address;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -27,6 +28,25 @@ pub struct L2BookData {
     pub levels: Vec<Vec<BookLevel>>,
 }
 
+// Every l2Book message, including the first one after subscribing, carries the full book
+impl L2BookData {
+    pub fn bids(&self) -> &[BookLevel] {
+        self.levels.first().map_or(&[], Vec::as_slice)
+    }
+
+    pub fn asks(&self) -> &[BookLevel] {
+        self.levels.get(1).map_or(&[], Vec::as_slice)
+    }
+}
+
+// (bid, ask); a side is None while it has no resting orders
+#[derive(Deserialize, Clone, Debug)]
+pub struct BboData {
+    pub coin: String,
+    pub time: u64,
+    pub bbo: (Option<BookLevel>, Option<BookLevel>),
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct AllMidsData {
     pub mids: HashMap<String, String>,
@@ -178,6 +198,9 @@ pub enum LedgerUpdate {
     AccountClassTransfer(AccountClassTransfer),
     SpotTransfer(SpotTransfer),
     SpotGenesis(SpotGenesis),
+    // Kinds without a typed variant yet, so one new kind does not fail the whole history
+    #[serde(other)]
+    Other,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -278,6 +301,13 @@ pub struct NotificationData {
 #[serde(rename_all = "camelCase")]
 pub struct WebData2Data {
     pub user: Address,
+    pub clearinghouse_state: UserStateResponse,
+    pub open_orders: Vec<OpenOrdersResponse>,
+    // `asset_ctxs[i]` is the context of `meta.universe[i]`
+    pub meta: Meta,
+    pub asset_ctxs: Vec<PerpsAssetCtx>,
+    pub agent_address: Option<Address>,
+    pub server_time: u64,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -329,3 +359,29 @@ pub struct SpotAssetCtx {
     pub circulating_supply: String,
     pub total_supply: String,
 }
+
+// `max_trade_szs` and `available_to_trade` are [buy, sell]
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveAssetDataData {
+    pub user: Address,
+    pub coin: String,
+    pub leverage: Leverage,
+    pub max_trade_szs: Vec<String>,
+    pub available_to_trade: Vec<String>,
+    pub mark_px: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct PostData {
+    pub id: u64,
+    pub response: PostResponse,
+}
+
+// `payload` is the info or action response, or the error message when `response_type` is "error"
+#[derive(Deserialize, Clone, Debug)]
+pub struct PostResponse {
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub payload: serde_json::Value,
+}
@@ -1,6 +1,6 @@
 use crate::{
     prelude::*,
-    ws::message_types::{AllMids, Candle, L2Book, OrderUpdates, Trades, User},
+    ws::message_types::{AllMids, Bbo, Candle, L2Book, OrderUpdates, Post, Trades, User},
     ActiveAssetCtx, Error, Notification, UserFills, UserFundings, UserNonFundingLedgerUpdates,
     WebData2,
 };
@@ -13,7 +13,7 @@ use std::{
     collections::HashMap,
     ops::DerefMut,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
     time::Duration,
@@ -21,7 +21,7 @@ use std::{
 use tokio::{
     net::TcpStream,
     spawn,
-    sync::{mpsc::UnboundedSender, Mutex},
+    sync::{mpsc::UnboundedSender, oneshot, Mutex},
     time,
 };
 use tokio_tungstenite::{
@@ -30,15 +30,51 @@ use tokio_tungstenite::{
     MaybeTlsStream, WebSocketStream,
 };
 
-
-use super::ActiveSpotAssetCtx;
+use super::{ActiveAssetData, ActiveSpotAssetCtx, Recorder};
 
 #[derive(Debug)]
 struct SubscriptionData {
-    sending_channel: UnboundedSender<Message>,
+    sending_channel: MessageSender,
     subscription_id: u32,
     id: String,
+    recorder: Option<Recorder>,
+}
+
+// Tagged senders receive each message together with the id of the subscription that produced it
+#[derive(Debug, Clone)]
+pub(crate) enum MessageSender {
+    Plain(UnboundedSender<Message>),
+    Tagged(UnboundedSender<(u32, Message)>),
+}
+
+impl MessageSender {
+    fn send(&self, subscription_id: u32, message: Message) -> Result<()> {
+        match self {
+            MessageSender::Plain(sender) => sender
+                .send(message)
+                .map_err(|e| Error::WsSend(e.to_string())),
+            MessageSender::Tagged(sender) => sender
+                .send((subscription_id, message))
+                .map_err(|e| Error::WsSend(e.to_string())),
+        }
+    }
+
+    fn same_channel(&self, other: &MessageSender) -> bool {
+        match (self, other) {
+            (MessageSender::Plain(sender), MessageSender::Plain(other)) => {
+                sender.same_channel(other)
+            }
+            (MessageSender::Tagged(sender), MessageSender::Tagged(other)) => {
+                sender.same_channel(other)
+            }
+            _ => false,
+        }
+    }
 }
+
+// Posts waiting for their response, keyed by request id
+type PendingPosts = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<serde_json::Value>>>>>;
+
 #[derive(Debug)]
 pub(crate) struct WsManager {
     stop_flag: Arc<AtomicBool>,
@@ -46,6 +82,8 @@ pub(crate) struct WsManager {
     subscriptions: Arc<Mutex<HashMap<String, Vec<SubscriptionData>>>>,
     subscription_id: u32,
     subscription_identifiers: HashMap<u32, String>,
+    pending_posts: PendingPosts,
+    post_id: AtomicU64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -57,6 +95,7 @@ pub enum Subscription {
     WebData2 { user: Address },
     Candle { coin: String, interval: String },
     L2Book { coin: String },
+    Bbo { coin: String },
     Trades { coin: String },
     OrderUpdates { user: Address },
     UserEvents { user: Address },
@@ -64,6 +103,7 @@ pub enum Subscription {
     UserFundings { user: Address },
     UserNonFundingLedgerUpdates { user: Address },
     ActiveAssetCtx { coin: String },
+    ActiveAssetData { user: Address, coin: String },
 }
 #[derive(Deserialize, Clone, Debug)]
 #[serde(tag = "channel")]
@@ -74,6 +114,7 @@ pub enum Message {
     AllMids(AllMids),
     Trades(Trades),
     L2Book(L2Book),
+    Bbo(Bbo),
     User(User),
     UserFills(UserFills),
     Candle(Candle),
@@ -85,7 +126,12 @@ pub enum Message {
     WebData2(WebData2),
     ActiveAssetCtx(ActiveAssetCtx),
     ActiveSpotAssetCtx(ActiveSpotAssetCtx),
+    ActiveAssetData(ActiveAssetData),
+    Post(Post),
     Pong,
+    /// Sent by the SDK after the connection was re-established and all subscriptions were renewed;
+    /// messages published while disconnected are not replayed
+    Reconnected,
 }
 
 #[derive(Serialize)]
@@ -94,15 +140,28 @@ pub(crate) struct SubscriptionSendData<'a> {
     subscription: &'a serde_json::Value,
 }
 
+#[derive(Serialize)]
+pub(crate) struct PostSendData<'a> {
+    method: &'static str,
+    id: u64,
+    request: &'a serde_json::Value,
+}
+
 #[derive(Serialize)]
 pub(crate) struct Ping {
     method: &'static str,
 }
 
 impl WsManager {
-    const SEND_PING_INTERVAL: u64 = 50;
+    const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+    const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+    const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
 
-    pub(crate) async fn new(url: String, reconnect: bool) -> Result<WsManager> {
+    pub(crate) async fn new(
+        url: String,
+        reconnect: bool,
+        ping_interval: Duration,
+    ) -> Result<WsManager> {
         let stop_flag = Arc::new(AtomicBool::new(false));
 
         let (writer, mut reader) = Self::connect(&url).await?.split();
@@ -111,20 +170,28 @@ impl WsManager {
         let subscriptions_map: HashMap<String, Vec<SubscriptionData>> = HashMap::new();
         let subscriptions = Arc::new(Mutex::new(subscriptions_map));
         let subscriptions_copy = Arc::clone(&subscriptions);
+        let pending_posts: PendingPosts = Arc::new(Mutex::new(HashMap::new()));
 
         {
             let writer = writer.clone();
             let stop_flag = Arc::clone(&stop_flag);
+            let pending_posts = Arc::clone(&pending_posts);
             let reader_fut = async move {
                 while !stop_flag.load(Ordering::Relaxed) {
                     if let Some(data) = reader.next().await {
-                        if let Err(err) =
-                            WsManager::parse_and_send_data(data, &subscriptions_copy).await
+                        if let Err(err) = WsManager::parse_and_send_data(
+                            data,
+                            &subscriptions_copy,
+                            &pending_posts,
+                        )
+                        .await
                         {
                             error!("Error processing data received by WsManager reader: {err}");
                         }
                     } else {
                         warn!("WsManager disconnected");
+                        // Responses to posts sent on the lost connection will never arrive
+                        pending_posts.lock().await.clear();
                         if let Err(err) = WsManager::send_to_all_subscriptions(
                             &subscriptions_copy,
                             Message::NoData,
@@ -134,42 +201,41 @@ impl WsManager {
                             warn!("Error sending disconnection notification err={err}");
                         }
                         if reconnect {
-                            // Always sleep for 1 second before attempting to reconnect so it does not spin during reconnecting. This could be enhanced with exponential backoff.
-                            tokio::time::sleep(Duration::from_secs(1)).await;
-                            info!("WsManager attempting to reconnect");
-                            match Self::connect(&url).await {
-                                Ok(ws) => {
-                                    let (new_writer, new_reader) = ws.split();
-                                    reader = new_reader;
-                                    let mut writer_guard = writer.lock().await;
-                                    *writer_guard = new_writer;
-                                    for (identifier, v) in subscriptions_copy.lock().await.iter() {
-                                        // TODO should these special keys be removed and instead use the simpler direct identifier mapping?
-                                        if identifier.eq("userEvents")
-                                            || identifier.eq("orderUpdates")
-                                        {
-                                            for subscription_data in v {
-                                                if let Err(err) = Self::subscribe(
-                                                    writer_guard.deref_mut(),
-                                                    &subscription_data.id,
-                                                )
-                                                .await
-                                                {
-                                                    error!(
-                                                        "Could not resubscribe {identifier}: {err}"
-                                                    );
-                                                }
-                                            }
-                                        } else if let Err(err) =
-                                            Self::subscribe(writer_guard.deref_mut(), identifier)
-                                                .await
+                            // Back off exponentially so an unreachable server is not hammered with connection attempts
+                            let mut delay = Self::INITIAL_RECONNECT_DELAY;
+                            while !stop_flag.load(Ordering::Relaxed) {
+                                time::sleep(delay).await;
+                                info!("WsManager attempting to reconnect");
+                                match Self::connect(&url).await {
+                                    Ok(ws) => {
+                                        let (new_writer, new_reader) = ws.split();
+                                        reader = new_reader;
+                                        let mut writer_guard = writer.lock().await;
+                                        *writer_guard = new_writer;
+                                        Self::resubscribe_all(
+                                            writer_guard.deref_mut(),
+                                            &subscriptions_copy,
+                                        )
+                                        .await;
+                                        drop(writer_guard);
+                                        info!("WsManager reconnect finished");
+                                        if let Err(err) = WsManager::send_to_all_subscriptions(
+                                            &subscriptions_copy,
+                                            Message::Reconnected,
+                                        )
+                                        .await
                                         {
-                                            error!("Could not resubscribe correctly {identifier}: {err}");
+                                            warn!(
+                                                "Error sending reconnection notification err={err}"
+                                            );
                                         }
+                                        break;
+                                    }
+                                    Err(err) => {
+                                        error!("Could not connect to websocket {err}");
+                                        delay = (delay * 2).min(Self::MAX_RECONNECT_DELAY);
                                     }
-                                    info!("WsManager reconnect finished");
                                 }
-                                Err(err) => error!("Could not connect to websocket {err}"),
                             }
                         } else {
                             error!("WsManager reconnection disabled. Will not reconnect and exiting reader task.");
@@ -196,7 +262,7 @@ impl WsManager {
                         }
                         Err(err) => error!("Error serializing ping message: {err}"),
                     }
-                    time::sleep(Duration::from_secs(Self::SEND_PING_INTERVAL)).await;
+                    time::sleep(ping_interval).await;
                 }
                 warn!("ws ping task stopped");
             };
@@ -209,14 +275,70 @@ impl WsManager {
             subscriptions,
             subscription_id: 0,
             subscription_identifiers: HashMap::new(),
+            pending_posts,
+            post_id: AtomicU64::new(0),
+        })
+    }
+
+    // `request` is the `{"type": "info" | "action", "payload": ...}` object of the post frame
+    // Resolves with the response to the post; awaiting it does not need the manager
+    pub(crate) async fn post(
+        &self,
+        request: serde_json::Value,
+    ) -> Result<oneshot::Receiver<Result<serde_json::Value>>> {
+        let id = self.post_id.fetch_add(1, Ordering::Relaxed);
+        let payload = serde_json::to_string(&PostSendData {
+            method: "post",
+            id,
+            request: &request,
         })
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        let (sender, receiver) = oneshot::channel();
+        self.pending_posts.lock().await.insert(id, sender);
+        if let Err(err) = self
+            .writer
+            .lock()
+            .await
+            .send(protocol::Message::Text(payload))
+            .await
+        {
+            self.pending_posts.lock().await.remove(&id);
+            return Err(Error::Websocket(err.to_string()));
+        }
+        Ok(receiver)
+    }
+
+    async fn resubscribe_all(
+        writer: &mut SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, protocol::Message>,
+        subscriptions: &Arc<Mutex<HashMap<String, Vec<SubscriptionData>>>>,
+    ) {
+        for (identifier, v) in subscriptions.lock().await.iter() {
+            // TODO should these special keys be removed and instead use the simpler direct identifier mapping?
+            if identifier.eq("userEvents") || identifier.eq("orderUpdates") {
+                for subscription_data in v {
+                    if let Err(err) = Self::subscribe(writer, &subscription_data.id).await {
+                        error!("Could not resubscribe {identifier}: {err}");
+                    }
+                }
+            } else if let Err(err) = Self::subscribe(writer, identifier).await {
+                error!("Could not resubscribe correctly {identifier}: {err}");
+            }
+        }
     }
 
+    // Also used when reconnecting, a handshake that hangs fails instead of blocking forever
     async fn connect(url: &str) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
-        Ok(connect_async(url)
+        let (stream, _) = time::timeout(Self::CONNECT_TIMEOUT, connect_async(url))
             .await
-            .map_err(|e| Error::Websocket(e.to_string()))?
-            .0)
+            .map_err(|_| {
+                Error::WebsocketConnect(format!(
+                    "{url}: timed out after {:?}",
+                    Self::CONNECT_TIMEOUT
+                ))
+            })?
+            .map_err(|e| Error::WebsocketConnect(format!("{url}: {e}")))?;
+        Ok(stream)
     }
 
     fn get_identifier(message: &Message) -> Result<String> {
@@ -242,6 +364,10 @@ impl WsManager {
                 coin: l2_book.data.coin.clone(),
             })
             .map_err(|e| Error::JsonParse(e.to_string())),
+            Message::Bbo(bbo) => serde_json::to_string(&Subscription::Bbo {
+                coin: bbo.data.coin.clone(),
+            })
+            .map_err(|e| Error::JsonParse(e.to_string())),
             Message::Candle(candle) => serde_json::to_string(&Subscription::Candle {
                 coin: candle.data.coin.clone(),
                 interval: candle.data.interval.clone(),
@@ -276,7 +402,17 @@ impl WsManager {
                 .map_err(|e| Error::JsonParse(e.to_string()));
                 s
             }
-            Message::SubscriptionResponse | Message::Pong => Ok(String::default()),
+            Message::ActiveAssetData(active_asset_data) => {
+                serde_json::to_string(&Subscription::ActiveAssetData {
+                    user: active_asset_data.data.user,
+                    coin: active_asset_data.data.coin.clone(),
+                })
+                .map_err(|e| Error::JsonParse(e.to_string()))
+            }
+            Message::SubscriptionResponse
+            | Message::Post(_)
+            | Message::Pong
+            | Message::Reconnected => Ok(String::default()),
             Message::NoData => Ok("".to_string()),
             Message::HyperliquidError(err) => Ok(format!("hyperliquid error: {err:?}")),
         }
@@ -285,6 +421,7 @@ impl WsManager {
     async fn parse_and_send_data(
         data: std::result::Result<protocol::Message, tungstenite::Error>,
         subscriptions: &Arc<Mutex<HashMap<String, Vec<SubscriptionData>>>>,
+        pending_posts: &PendingPosts,
     ) -> Result<()> {
         match data {
             Ok(data) => match data.into_text() {
@@ -294,6 +431,12 @@ impl WsManager {
                     }
                     let message = serde_json::from_str::<Message>(&data)
                         .map_err(|e| Error::JsonParse(e.to_string()))?;
+                    if let Message::Post(post) = message {
+                        if let Some(sender) = pending_posts.lock().await.remove(&post.data.id) {
+                            let _ = sender.send(Self::post_result(post));
+                        }
+                        return Ok(());
+                    }
                     let identifier = WsManager::get_identifier(&message)?;
                     if identifier.is_empty() {
                         return Ok(());
@@ -303,10 +446,14 @@ impl WsManager {
                     let mut res = Ok(());
                     if let Some(subscription_datas) = subscriptions.get_mut(&identifier) {
                         for subscription_data in subscription_datas {
+                            if let Some(recorder) = subscription_data.recorder.as_mut() {
+                                if let Err(e) = recorder.record(&data) {
+                                    res = Err(e);
+                                }
+                            }
                             if let Err(e) = subscription_data
                                 .sending_channel
-                                .send(message.clone())
-                                .map_err(|e| Error::WsSend(e.to_string()))
+                                .send(subscription_data.subscription_id, message.clone())
                             {
                                 res = Err(e);
                             }
@@ -334,6 +481,18 @@ impl WsManager {
         }
     }
 
+    fn post_result(post: Post) -> Result<serde_json::Value> {
+        let response = post.data.response;
+        if response.response_type == "error" {
+            let message = match response.payload {
+                serde_json::Value::String(message) => message,
+                payload => payload.to_string(),
+            };
+            return Err(Error::WsPost(message));
+        }
+        Ok(response.payload)
+    }
+
     async fn send_to_all_subscriptions(
         subscriptions: &Arc<Mutex<HashMap<String, Vec<SubscriptionData>>>>,
         message: Message,
@@ -344,8 +503,7 @@ impl WsManager {
             for subscription_data in subscription_datas {
                 if let Err(e) = subscription_data
                     .sending_channel
-                    .send(message.clone())
-                    .map_err(|e| Error::WsSend(e.to_string()))
+                    .send(subscription_data.subscription_id, message.clone())
                 {
                     res = Err(e);
                 }
@@ -389,7 +547,8 @@ impl WsManager {
     pub(crate) async fn add_subscription(
         &mut self,
         identifier: String,
-        sending_channel: UnboundedSender<Message>,
+        sending_channel: MessageSender,
+        mut recorder: Option<Recorder>,
     ) -> Result<u32> {
         let mut subscriptions = self.subscriptions.lock().await;
         let identifier_entry = if let Subscription::UserEvents { user: _ } =
@@ -409,6 +568,12 @@ impl WsManager {
             .entry(identifier_entry.clone())
             .or_insert(Vec::new());
 
+        if let Some(subscription_id) =
+            Self::resubscribe(subscriptions, &identifier, &sending_channel, &mut recorder)
+        {
+            return Ok(subscription_id);
+        }
+
         if !subscriptions.is_empty() && identifier_entry.eq("userEvents") {
             return Err(Error::UserEvents);
         }
@@ -424,12 +589,51 @@ impl WsManager {
             sending_channel,
             subscription_id,
             id: identifier,
+            recorder,
         });
 
         self.subscription_id += 1;
         Ok(subscription_id)
     }
 
+    // Subscribing the same channel to the same feed twice would only duplicate every message, so
+    // the existing subscription is reused and takes over the recorder if one is passed
+    fn resubscribe(
+        subscriptions: &mut [SubscriptionData],
+        identifier: &str,
+        sending_channel: &MessageSender,
+        recorder: &mut Option<Recorder>,
+    ) -> Option<u32> {
+        let existing = subscriptions.iter_mut().find(|subscription_data| {
+            subscription_data.id == identifier
+                && subscription_data
+                    .sending_channel
+                    .same_channel(sending_channel)
+        })?;
+        if let Some(recorder) = recorder.take() {
+            existing.recorder = Some(recorder);
+        }
+        Some(existing.subscription_id)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.subscription_identifiers.is_empty()
+    }
+
+    pub(crate) fn active_subscriptions(&self) -> Result<Vec<(u32, Subscription)>> {
+        let mut active_subscriptions = self
+            .subscription_identifiers
+            .iter()
+            .map(|(subscription_id, identifier)| {
+                serde_json::from_str(identifier)
+                    .map(|subscription| (*subscription_id, subscription))
+                    .map_err(|e| Error::JsonParse(e.to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        active_subscriptions.sort_by_key(|(subscription_id, _)| *subscription_id);
+        Ok(active_subscriptions)
+    }
+
     pub(crate) async fn remove_subscription(&mut self, subscription_id: u32) -> Result<()> {
         let identifier = self
             .subscription_identifiers
@@ -476,3 +680,361 @@ impl Drop for WsManager {
         self.stop_flag.store(true, Ordering::Relaxed);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{helpers::now_timestamp_ms, AssetCtx};
+
+    fn identifier_of(raw: &str) -> Result<String> {
+        let message =
+            serde_json::from_str::<Message>(raw).map_err(|e| Error::JsonParse(e.to_string()))?;
+        WsManager::get_identifier(&message)
+    }
+
+    #[test]
+    fn candle_messages_route_to_their_subscription() -> Result<()> {
+        let subscription = serde_json::to_string(&Subscription::Candle {
+            coin: "BTC".to_string(),
+            interval: "1m".to_string(),
+        })
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(
+            subscription,
+            r#"{"type":"candle","coin":"BTC","interval":"1m"}"#
+        );
+
+        let identifier = identifier_of(
+            r#"{"channel":"candle","data":{"t":1700000000000,"T":1700000059999,"s":"BTC","i":"1m",
+                "o":"37000.0","c":"37010.0","h":"37020.0","l":"36990.0","v":"12.5","n":42}}"#,
+        )?;
+        assert_eq!(identifier, subscription);
+        Ok(())
+    }
+
+    #[test]
+    fn l2_book_messages_expose_both_sides() -> Result<()> {
+        let raw = r#"{"channel":"l2Book","data":{"coin":"ETH","time":1700000000000,"levels":[
+            [{"px":"1999.9","sz":"1.5","n":2},{"px":"1999.8","sz":"3","n":1}],
+            [{"px":"2000.1","sz":"0.7","n":1}]
+        ]}}"#;
+        let subscription = serde_json::to_string(&Subscription::L2Book {
+            coin: "ETH".to_string(),
+        })
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(identifier_of(raw)?, subscription);
+
+        let Message::L2Book(l2_book) =
+            serde_json::from_str(raw).map_err(|e| Error::JsonParse(e.to_string()))?
+        else {
+            panic!("expected an l2Book message");
+        };
+        assert_eq!(l2_book.data.bids().len(), 2);
+        assert_eq!(l2_book.data.asks()[0].px, "2000.1");
+        Ok(())
+    }
+
+    #[test]
+    fn active_asset_ctx_messages_carry_the_perp_context() -> Result<()> {
+        let raw = r#"{"channel":"activeAssetCtx","data":{"coin":"BTC","ctx":{"dayNtlVlm":"1169046.29","prevDayPx":"64900.0",
+            "markPx":"65010.0","midPx":"65009.5","funding":"0.0000125","openInterest":"1520.4","oraclePx":"65012.0"}}}"#;
+        let subscription = serde_json::to_string(&Subscription::ActiveAssetCtx {
+            coin: "BTC".to_string(),
+        })
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(identifier_of(raw)?, subscription);
+
+        let Message::ActiveAssetCtx(active_asset_ctx) =
+            serde_json::from_str(raw).map_err(|e| Error::JsonParse(e.to_string()))?
+        else {
+            panic!("expected an activeAssetCtx message");
+        };
+        let AssetCtx::Perps(ctx) = active_asset_ctx.data.ctx else {
+            panic!("expected a perp asset context");
+        };
+        assert_eq!(ctx.funding, "0.0000125");
+        assert_eq!(ctx.shared.mark_px, "65010.0");
+        Ok(())
+    }
+
+    #[test]
+    fn active_asset_data_messages_route_by_user_and_coin() -> Result<()> {
+        let raw = r#"{"channel":"activeAssetData","data":{"user":"0xc64cc00b46101bd40aa1c3121195e85c0b0918d8","coin":"ETH",
+            "leverage":{"type":"cross","value":20},"maxTradeSzs":["12.5","11.9"],"availableToTrade":["25000.0","23800.0"],"markPx":"2000.5"}}"#;
+        let subscription = serde_json::to_string(&Subscription::ActiveAssetData {
+            user: "0xc64cc00b46101bd40aa1c3121195e85c0b0918d8"
+                .parse()
+                .map_err(|_| Error::JsonParse("invalid address".to_string()))?,
+            coin: "ETH".to_string(),
+        })
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(identifier_of(raw)?, subscription);
+
+        let Message::ActiveAssetData(active_asset_data) =
+            serde_json::from_str(raw).map_err(|e| Error::JsonParse(e.to_string()))?
+        else {
+            panic!("expected an activeAssetData message");
+        };
+        assert_eq!(active_asset_data.data.leverage.value, 20);
+        assert_eq!(active_asset_data.data.max_trade_szs, vec!["12.5", "11.9"]);
+        Ok(())
+    }
+
+    #[test]
+    fn bbo_messages_carry_the_inside_market() -> Result<()> {
+        let raw = r#"{"channel":"bbo","data":{"coin":"ETH","time":1700000000000,"bbo":[
+            {"px":"1999.9","sz":"1.5","n":2},null
+        ]}}"#;
+        let subscription = serde_json::to_string(&Subscription::Bbo {
+            coin: "ETH".to_string(),
+        })
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(subscription, r#"{"type":"bbo","coin":"ETH"}"#);
+        assert_eq!(identifier_of(raw)?, subscription);
+
+        let Message::Bbo(bbo) =
+            serde_json::from_str(raw).map_err(|e| Error::JsonParse(e.to_string()))?
+        else {
+            panic!("expected a bbo message");
+        };
+        let (bid, ask) = bbo.data.bbo;
+        assert_eq!(bid.map(|level| level.px).as_deref(), Some("1999.9"));
+        assert!(ask.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn web_data2_messages_expose_typed_sections() -> Result<()> {
+        let raw = r#"{"channel":"webData2","data":{"user":"0x0bd592152216b0d21175c8cf856b5be05f3a7c3e",
+            "clearinghouseState":{"assetPositions":[],"withdrawable":"100.0",
+                "marginSummary":{"accountValue":"100.0","totalMarginUsed":"0.0","totalNtlPos":"0.0","totalRawUsd":"100.0"},
+                "crossMarginSummary":{"accountValue":"100.0","totalMarginUsed":"0.0","totalNtlPos":"0.0","totalRawUsd":"100.0"}},
+            "openOrders":[{"coin":"BTC","limitPx":"60000.0","oid":1234,"side":"B","sz":"0.01","timestamp":1700000000000}],
+            "meta":{"universe":[{"name":"BTC","szDecimals":5,"maxLeverage":50}]},
+            "assetCtxs":[{"dayNtlVlm":"1169046.29","prevDayPx":"64900.0","markPx":"65010.0","midPx":null,
+                "funding":"0.0000125","openInterest":"1520.4","oraclePx":"65012.0"}],
+            "agentAddress":null,"serverTime":1700000000123}}"#;
+
+        let Message::WebData2(web_data2) =
+            serde_json::from_str(raw).map_err(|e| Error::JsonParse(e.to_string()))?
+        else {
+            panic!("expected a webData2 message");
+        };
+        let data = web_data2.data;
+        assert_eq!(data.clearinghouse_state.withdrawable, "100.0");
+        assert_eq!(data.open_orders[0].oid, 1234);
+        assert_eq!(data.meta.universe[0].name, "BTC");
+        assert_eq!(data.asset_ctxs[0].funding, "0.0000125");
+        assert!(data.agent_address.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn trades_in_one_frame_are_delivered_together() -> Result<()> {
+        let identifier = serde_json::to_string(&Subscription::Trades {
+            coin: "SOL".to_string(),
+        })
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let (sending_channel, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let subscriptions = Arc::new(Mutex::new(HashMap::from([(
+            identifier.clone(),
+            vec![SubscriptionData {
+                sending_channel: MessageSender::Plain(sending_channel),
+                subscription_id: 0,
+                id: identifier,
+                recorder: None,
+            }],
+        )])));
+
+        let raw = r#"{"channel":"trades","data":[
+            {"coin":"SOL","side":"B","px":"150.1","sz":"2","time":1700000000000,"hash":"0x1","tid":1},
+            {"coin":"SOL","side":"A","px":"150.0","sz":"1","time":1700000000001,"hash":"0x2","tid":2}
+        ]}"#;
+        WsManager::parse_and_send_data(
+            Ok(protocol::Message::Text(raw.to_string())),
+            &subscriptions,
+            &Arc::new(Mutex::new(HashMap::new())),
+        )
+        .await?;
+
+        let Ok(Message::Trades(trades)) = receiver.try_recv() else {
+            panic!("expected a trades message");
+        };
+        assert_eq!(trades.data.len(), 2);
+        assert!(receiver.try_recv().is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn feeds_on_one_connection_reach_only_their_own_channel() -> Result<()> {
+        let mut receivers = Vec::new();
+        let mut subscriptions = HashMap::new();
+        for (subscription_id, coin) in ["BTC", "ETH"].into_iter().enumerate() {
+            let identifier = serde_json::to_string(&Subscription::Bbo {
+                coin: coin.to_string(),
+            })
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+            let (sending_channel, receiver) = tokio::sync::mpsc::unbounded_channel();
+            subscriptions.insert(
+                identifier.clone(),
+                vec![SubscriptionData {
+                    sending_channel: MessageSender::Plain(sending_channel),
+                    subscription_id: subscription_id as u32,
+                    id: identifier,
+                    recorder: None,
+                }],
+            );
+            receivers.push(receiver);
+        }
+        let subscriptions = Arc::new(Mutex::new(subscriptions));
+
+        let raw =
+            r#"{"channel":"bbo","data":{"coin":"ETH","time":1700000000000,"bbo":[null,null]}}"#;
+        WsManager::parse_and_send_data(
+            Ok(protocol::Message::Text(raw.to_string())),
+            &subscriptions,
+            &Arc::new(Mutex::new(HashMap::new())),
+        )
+        .await?;
+
+        assert!(receivers[0].try_recv().is_err());
+        assert!(matches!(receivers[1].try_recv(), Ok(Message::Bbo(bbo)) if bbo.data.coin == "ETH"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tagged_channels_receive_the_subscription_id() -> Result<()> {
+        let (sending_channel, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut subscriptions = HashMap::new();
+        for (subscription_id, coin) in [(3, "BTC"), (4, "ETH")] {
+            let identifier = serde_json::to_string(&Subscription::Bbo {
+                coin: coin.to_string(),
+            })
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+            subscriptions.insert(
+                identifier.clone(),
+                vec![SubscriptionData {
+                    sending_channel: MessageSender::Tagged(sending_channel.clone()),
+                    subscription_id,
+                    id: identifier,
+                    recorder: None,
+                }],
+            );
+        }
+        let subscriptions = Arc::new(Mutex::new(subscriptions));
+
+        let raw =
+            r#"{"channel":"bbo","data":{"coin":"ETH","time":1700000000000,"bbo":[null,null]}}"#;
+        WsManager::parse_and_send_data(
+            Ok(protocol::Message::Text(raw.to_string())),
+            &subscriptions,
+            &Arc::new(Mutex::new(HashMap::new())),
+        )
+        .await?;
+
+        assert!(matches!(receiver.try_recv(), Ok((4, Message::Bbo(_)))));
+        assert!(receiver.try_recv().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn all_mids_subscribe_payload_has_no_extra_fields() -> Result<()> {
+        let identifier = serde_json::to_string(&Subscription::AllMids)
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let subscription = serde_json::from_str::<serde_json::Value>(&identifier)
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let payload = serde_json::to_string(&SubscriptionSendData {
+            method: "subscribe",
+            subscription: &subscription,
+        })
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(
+            payload,
+            r#"{"method":"subscribe","subscription":{"type":"allMids"}}"#
+        );
+        assert_eq!(
+            identifier_of(r#"{"channel":"allMids","data":{"mids":{"BTC":"65000.5"}}}"#)?,
+            identifier
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn post_responses_resolve_the_matching_request() -> Result<()> {
+        let (sender, receiver) = oneshot::channel();
+        let (other_sender, mut other_receiver) = oneshot::channel();
+        let pending_posts: PendingPosts =
+            Arc::new(Mutex::new(HashMap::from([(7, sender), (8, other_sender)])));
+
+        let raw = r#"{"channel":"post","data":{"id":7,"response":{"type":"error","payload":"Order has zero size"}}}"#;
+        WsManager::parse_and_send_data(
+            Ok(protocol::Message::Text(raw.to_string())),
+            &Arc::new(Mutex::new(HashMap::new())),
+            &pending_posts,
+        )
+        .await?;
+
+        let response = receiver.await.map_err(|e| Error::WsPost(e.to_string()))?;
+        assert!(
+            matches!(response, Err(Error::WsPost(message)) if message == "Order has zero size")
+        );
+        assert!(other_receiver.try_recv().is_err());
+        assert!(pending_posts.lock().await.contains_key(&8));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn failed_connects_name_the_url() {
+        let err = WsManager::new(
+            "ws://127.0.0.1:1/ws".to_string(),
+            false,
+            Duration::from_secs(50),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, Error::WebsocketConnect(e) if e.starts_with("ws://127.0.0.1:1/ws")));
+    }
+
+    #[tokio::test]
+    async fn resubscribing_with_a_recorder_records_the_existing_feed() -> Result<()> {
+        let identifier = serde_json::to_string(&Subscription::AllMids)
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let (sending_channel, _receiver) = tokio::sync::mpsc::unbounded_channel();
+        let sending_channel = MessageSender::Plain(sending_channel);
+        let mut subscriptions = vec![SubscriptionData {
+            sending_channel: sending_channel.clone(),
+            subscription_id: 2,
+            id: identifier.clone(),
+            recorder: None,
+        }];
+
+        let path =
+            std::env::temp_dir().join(format!("hl-resubscribe-{}.jsonl", now_timestamp_ms()));
+        let mut recorder = Some(Recorder::create(&path)?);
+        assert_eq!(
+            WsManager::resubscribe(
+                &mut subscriptions,
+                &identifier,
+                &sending_channel,
+                &mut recorder
+            ),
+            Some(2)
+        );
+        assert!(recorder.is_none());
+
+        let subscriptions = Arc::new(Mutex::new(HashMap::from([(identifier, subscriptions)])));
+        let raw = r#"{"channel":"allMids","data":{"mids":{"BTC":"65000.5"}}}"#;
+        WsManager::parse_and_send_data(
+            Ok(protocol::Message::Text(raw.to_string())),
+            &subscriptions,
+            &Arc::new(Mutex::new(HashMap::new())),
+        )
+        .await?;
+        drop(subscriptions);
+        let recording =
+            std::fs::read_to_string(&path).map_err(|e| Error::Recording(e.to_string()))?;
+        std::fs::remove_file(&path).map_err(|e| Error::Recording(e.to_string()))?;
+        assert_eq!(recording.lines().count(), 1);
+        Ok(())
+    }
+}